@@ -13,6 +13,7 @@ export type BarCanyonKillBossMetadata = {
 
 export type BarCanyonKillBossNpc = {
   type: number;
+  boss: "miser_khaolak" | "ironhand_baulur" | "unknown";
   level: number;
   location: BarCanyonKillBossLocation;
 };
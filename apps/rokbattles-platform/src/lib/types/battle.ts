@@ -4,6 +4,10 @@ export type BattleMail = {
   summary: BattleSummary;
   opponents: readonly BattleOpponent[];
   timeline: BattleTimeline;
+  analytics: BattleAnalytics;
+  troop_curve: BattleTroopCurve;
+  classification: BattleClassification;
+  npc: BattleNpcTotals;
 };
 
 export type BattleMetadata = {
@@ -13,6 +17,7 @@ export type BattleMetadata = {
   server_id: number;
   mail_role: string;
   kvk: boolean;
+  season: BattleSeason;
 };
 
 export type BattleSender = BattlePlayer;
@@ -31,13 +36,35 @@ export type BattlePlayer = {
   kingdom_id: number | null;
   alliance: BattleAlliance;
   alliance_building_id: number | null;
+  alliance_building: "flag" | "stronghold" | "horse_fort" | "unknown" | null;
   castle: BattleCastle;
   tracking_key: string;
   camp_id: number | null;
   rally: boolean | null;
   structure_id: number | null;
+  structure:
+    | "shrine_of_war"
+    | "shrine_of_life"
+    | "sky_altar"
+    | "ark_unidentified"
+    | "outpost_of_iset"
+    | "great_ziggurat"
+    | "level_7_pass"
+    | "obelisk"
+    | "unknown"
+    | null;
   commanders: BattleCommanderSet;
   app_id: number | null;
+  app_client:
+    | "international"
+    | "chinese"
+    | "vietnamese"
+    | "korean"
+    | "japanese"
+    | "chinese_huawei"
+    | "chinese_taiwan"
+    | "unknown"
+    | null;
   app_uid: number | null;
   avatar_url: string | null;
   frame_url: string | null;
@@ -76,11 +103,30 @@ export type BattleCommander = {
   awakened: boolean | null;
   star_level: number | null;
   equipment: string | null;
+  equipment_slots: readonly BattleCommanderEquipmentSlot[] | null;
   skills: readonly BattleCommanderSkill[] | null;
   relics: readonly BattleCommanderRelic[] | null;
   armaments: readonly BattleCommanderArmament[] | null;
 };
 
+export type BattleCommanderEquipmentSlot = {
+  slot: number;
+  id: number;
+  craft: number | null;
+  attributes: number | null;
+  special_talent: BattleEquipmentSpecialTalent | null;
+  iconic_level: number | null;
+};
+
+export type BattleEquipmentSpecialTalent =
+  | "infantry"
+  | "archer"
+  | "cavalry"
+  | "integration"
+  | "leadership"
+  | "engineering"
+  | "unknown";
+
 export type BattleCommanderSkill = {
   id: number;
   level: number;
@@ -93,8 +139,24 @@ export type BattleCommanderRelic = {
 
 export type BattleCommanderArmament = {
   id: number;
+  kind: BattleArmamentKind;
   affix: string;
   buffs: string;
+  inscriptions: readonly BattleArmamentInscription[] | null;
+  stats: readonly BattleArmamentStat[] | null;
+};
+
+export type BattleArmamentKind = "scroll" | "instrument" | "flag" | "emblem" | "unknown";
+
+export type BattleArmamentInscription = {
+  id: number;
+  category: BattleArmamentKind;
+  rarity: "common" | "rare" | "special" | "unknown";
+};
+
+export type BattleArmamentStat = {
+  id: number;
+  value: number;
 };
 
 export type BattleParticipant = {
@@ -104,10 +166,8 @@ export type BattleParticipant = {
   alliance: {
     abbreviation: string;
   };
-  commanders: {
-    primary: BattleParticipantCommander;
-    secondary: BattleParticipantCommander;
-  };
+  commanders: BattleCommanderSet;
+  extra: Record<string, unknown>;
 };
 
 export type BattleParticipantCommander = {
@@ -181,6 +241,7 @@ export type BattleTimeline = {
   start_tick: number;
   sampling: readonly BattleTimelineSample[];
   events: readonly BattleTimelineEvent[];
+  event_counts: Partial<Record<BattleTimelineEvent["kind"], number>>;
 };
 
 export type BattleTimelineSample = {
@@ -188,13 +249,20 @@ export type BattleTimelineSample = {
   count: number;
 };
 
-export type BattleTimelineEvent = {
+export type BattleTimelineEvent =
+  | BattleTimelineReinforcementEvent
+  | BattleTimelineCastleHealingEvent
+  | BattleTimelineRawEvent;
+
+export type BattleTimelineReinforcementEvent = {
   tick: number;
   type: number;
+  kind: "reinforcement_join" | "reinforcement_leave";
   event_id: number | null;
   player_id: number;
   player_name: string;
   count: number | null;
+  is_self: boolean | null;
   avatar_url: string | null;
   frame_url: string | null;
   commanders: {
@@ -202,3 +270,127 @@ export type BattleTimelineEvent = {
     secondary: BattleParticipantCommander;
   };
 };
+
+export type BattleTimelineCastleHealingEvent = {
+  tick: number;
+  type: number;
+  kind: "castle_healing";
+  count: number | null;
+  is_self: boolean | null;
+};
+
+export type BattleTimelineRawEvent = {
+  tick: number;
+  type: number;
+  kind: "raw";
+  count: number | null;
+  raw: Record<string, unknown>;
+};
+
+export type BattleAnalytics = {
+  attacks: readonly BattleAttackAnalytics[];
+  total: BattleCombatAnalytics;
+};
+
+export type BattleAttackAnalytics = BattleCombatAnalytics & {
+  attack_id: string;
+};
+
+export type BattleCombatAnalytics = {
+  duration_ticks: number | null;
+  sender: BattleSideAnalytics;
+  opponent: BattleSideAnalytics;
+  power_swing: BattlePowerSwing | null;
+};
+
+export type BattleSideAnalytics = {
+  kill_points: number | null;
+  losses: number | null;
+  kill_loss_ratio: number | null;
+  troops_lost_per_kill_point: number | null;
+  heal_ratio: number | null;
+  wound_to_death_conversion: number | null;
+};
+
+export type BattlePowerSwing = {
+  power: number;
+  attack_power: number;
+  skill_power: number;
+};
+
+export type BattleTroopCurve = {
+  start_tick: number | null;
+  end_tick: number | null;
+  start_time: number | null;
+  end_time: number | null;
+  points: readonly BattleTroopCurvePoint[];
+  segments: readonly BattleTroopCurveSegment[];
+};
+
+export type BattleTroopCurvePoint = {
+  tick: number;
+  time: number | null;
+  count: number | null;
+  delta: number | null;
+  source: "sample" | "reinforcement_join" | "reinforcement_leave" | "attack_start" | "attack_end";
+  attack_id: string | null;
+};
+
+export type BattleTroopCurveSegment = {
+  attack_id: string;
+  player_id: number | null;
+  start_tick: number;
+  end_tick: number;
+  start_time: number | null;
+  end_time: number | null;
+  start_count: number | null;
+  end_count: number | null;
+  min_count: number | null;
+  max_count: number | null;
+  joined: number;
+  left: number;
+};
+
+export type BattleKind =
+  | "dungeon"
+  | "npc"
+  | "ark_objective"
+  | "kvk_objective"
+  | "alliance_building"
+  | "garrison_defense"
+  | "rally"
+  | "open_field";
+
+export type BattleClassification = {
+  kind: BattleKind;
+  counts: Partial<Record<BattleKind, number>>;
+  attacks: readonly BattleAttackClassification[];
+};
+
+export type BattleAttackClassification = {
+  attack_id: string;
+  kind: BattleKind;
+};
+
+export type BattleNpcTotals = {
+  attacks: number;
+  kills: number;
+  experience: number;
+  loot: readonly BattleLoot[];
+};
+
+export type BattleSeason = {
+  home_kingdom_id: number | null;
+  battle_kingdom_id: number;
+  opponent_kingdom_ids: readonly number[];
+  cross_kingdom: boolean | null;
+  supreme_strife: BattleSupremeStrife;
+  ark: BattleArkContext | null;
+};
+
+export type BattleArkContext = {
+  structures: readonly {
+    id: number;
+    structure: string;
+  }[];
+};
@@ -0,0 +1,209 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use binidx_decoder::CatalogEntry;
+
+/// Language name for a `*.bin` whose stem equals its index table's stem.
+pub(crate) const DEFAULT_LANGUAGE: &str = "default";
+
+/// A value table matched with the index table it is read against.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TablePair {
+    pub(crate) language: String,
+    pub(crate) index: PathBuf,
+    pub(crate) bin: PathBuf,
+}
+
+/// A decoded table pair as recorded in the merged output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct MergedTable {
+    pub(crate) pair: TablePair,
+    pub(crate) xor: u8,
+    pub(crate) rows: usize,
+}
+
+/// Table pairs found in a directory and the value tables left unpaired.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct DiscoveredTables {
+    pub(crate) pairs: Vec<TablePair>,
+    pub(crate) skipped: Vec<PathBuf>,
+}
+
+/// Strings from every table pair keyed by string key, then by language.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct MergedCatalog {
+    pub(crate) languages: BTreeSet<String>,
+    pub(crate) tables: Vec<MergedTable>,
+    pub(crate) skipped: Vec<PathBuf>,
+    pub(crate) strings: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+impl MergedCatalog {
+    /// Add decoded rows for a pair. The first value seen for a key and language wins.
+    pub(crate) fn insert(&mut self, pair: &TablePair, xor: u8, rows: &[CatalogEntry]) {
+        self.languages.insert(pair.language.clone());
+        for row in rows {
+            self.strings
+                .entry(row.key.clone())
+                .or_default()
+                .entry(pair.language.clone())
+                .or_insert_with(|| row.value.clone());
+        }
+        self.tables.push(MergedTable {
+            pair: pair.clone(),
+            xor,
+            rows: rows.len(),
+        });
+    }
+}
+
+/// Pair every `*.bin` in `dir` with an index table.
+///
+/// `name.bin` pairs with `name.idx`; otherwise `name_LANG.bin` pairs with the
+/// longest matching `name.idx` and `LANG` becomes the column. Value tables
+/// without an index table are returned as skipped.
+pub(crate) fn discover_pairs(dir: &Path) -> anyhow::Result<DiscoveredTables> {
+    let mut index_stems = BTreeMap::new();
+    let mut bins = Vec::new();
+    let entries =
+        std::fs::read_dir(dir).with_context(|| format!("failed to read {}", dir.display()))?;
+    for entry in entries {
+        let path = entry
+            .with_context(|| format!("failed to read {}", dir.display()))?
+            .path();
+        if !path.is_file() {
+            continue;
+        }
+        let Some(stem) = path.file_stem().and_then(|stem| stem.to_str()) else {
+            continue;
+        };
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("idx") => {
+                index_stems.insert(stem.to_owned(), path.clone());
+            }
+            Some(ext) if ext.eq_ignore_ascii_case("bin") => {
+                bins.push((stem.to_owned(), path.clone()))
+            }
+            _ => {}
+        }
+    }
+    bins.sort();
+
+    let mut discovered = DiscoveredTables::default();
+    for (stem, bin) in bins {
+        match pair_for_stem(&index_stems, &stem) {
+            Some((index, language)) => discovered.pairs.push(TablePair {
+                language,
+                index: index.clone(),
+                bin,
+            }),
+            None => discovered.skipped.push(bin),
+        }
+    }
+    Ok(discovered)
+}
+
+fn pair_for_stem<'a>(
+    index_stems: &'a BTreeMap<String, PathBuf>,
+    stem: &str,
+) -> Option<(&'a PathBuf, String)> {
+    if let Some(index) = index_stems.get(stem) {
+        return Some((index, DEFAULT_LANGUAGE.to_owned()));
+    }
+
+    index_stems
+        .iter()
+        .filter_map(|(base, index)| {
+            let language = stem.strip_prefix(base.as_str())?.strip_prefix('_')?;
+            (!language.is_empty()).then_some((base.len(), index, language))
+        })
+        .max_by_key(|(base_len, _, _)| *base_len)
+        .map(|(_, index, language)| (index, language.to_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MergedCatalog, TablePair, discover_pairs};
+    use binidx_decoder::CatalogEntry;
+    use std::path::PathBuf;
+
+    fn row(key: &str, value: &str) -> CatalogEntry {
+        CatalogEntry {
+            index: 1,
+            key: key.to_owned(),
+            value: value.to_owned(),
+        }
+    }
+
+    fn pair(language: &str) -> TablePair {
+        TablePair {
+            language: language.to_owned(),
+            index: PathBuf::from("table.idx"),
+            bin: PathBuf::from(format!("table_{language}.bin")),
+        }
+    }
+
+    #[test]
+    fn discover_pairs_matches_language_suffixes() {
+        let temp = tempfile::tempdir().expect("temp dir");
+        for name in [
+            "table.idx",
+            "table_en.bin",
+            "table_zh_cn.bin",
+            "table_skill.idx",
+            "table_skill_de.bin",
+            "misc.idx",
+            "misc.bin",
+            "orphan_en.bin",
+            "notes.txt",
+        ] {
+            std::fs::write(temp.path().join(name), []).expect("write file");
+        }
+
+        let discovered = discover_pairs(temp.path()).expect("discover pairs");
+        let summary = discovered
+            .pairs
+            .iter()
+            .map(|pair| {
+                (
+                    pair.bin.file_name().unwrap().to_str().unwrap(),
+                    pair.index.file_name().unwrap().to_str().unwrap(),
+                    pair.language.as_str(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            [
+                ("misc.bin", "misc.idx", "default"),
+                ("table_en.bin", "table.idx", "en"),
+                ("table_skill_de.bin", "table_skill.idx", "de"),
+                ("table_zh_cn.bin", "table.idx", "zh_cn"),
+            ]
+        );
+        assert_eq!(discovered.skipped, [temp.path().join("orphan_en.bin")]);
+    }
+
+    #[test]
+    fn merged_catalog_keeps_a_column_per_language() {
+        let mut merged = MergedCatalog::default();
+        merged.insert(
+            &pair("en"),
+            0x32,
+            &[row("N_1", "Sword"), row("N_2", "Shield")],
+        );
+        merged.insert(
+            &pair("de"),
+            0x33,
+            &[row("N_1", "Schwert"), row("N_1", "Axt")],
+        );
+
+        assert_eq!(merged.languages.iter().collect::<Vec<_>>(), ["de", "en"]);
+        assert_eq!(merged.strings["N_1"]["de"], "Schwert");
+        assert_eq!(merged.strings["N_1"]["en"], "Sword");
+        assert!(!merged.strings["N_2"].contains_key("de"));
+        assert_eq!(merged.tables[1].xor, 0x33);
+        assert_eq!(merged.tables[1].rows, 2);
+    }
+}
@@ -0,0 +1,153 @@
+use std::collections::BTreeMap;
+
+use binidx_decoder::CatalogEntry;
+
+/// How a key differs between the old and new catalog.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+impl ChangeKind {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            ChangeKind::Added => "added",
+            ChangeKind::Removed => "removed",
+            ChangeKind::Changed => "changed",
+        }
+    }
+}
+
+/// One differing key with its old and new values.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct KeyChange<'a> {
+    pub(crate) kind: ChangeKind,
+    pub(crate) key: &'a str,
+    pub(crate) old: Option<&'a str>,
+    pub(crate) new: Option<&'a str>,
+}
+
+/// Key-level differences between two catalogs, ordered by key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CatalogDiff<'a> {
+    pub(crate) changes: Vec<KeyChange<'a>>,
+    pub(crate) unchanged: usize,
+}
+
+impl CatalogDiff<'_> {
+    pub(crate) fn count(&self, kind: ChangeKind) -> usize {
+        self.changes
+            .iter()
+            .filter(|change| change.kind == kind)
+            .count()
+    }
+}
+
+/// Compare rows by key. When a key repeats, its first row is used.
+pub(crate) fn diff_catalogs<'a>(
+    old: &'a [CatalogEntry],
+    new: &'a [CatalogEntry],
+) -> CatalogDiff<'a> {
+    let old = values_by_key(old);
+    let mut new = values_by_key(new);
+
+    let mut changes = Vec::new();
+    let mut unchanged = 0;
+    for (key, old_value) in old {
+        match new.remove(key) {
+            Some(new_value) if new_value == old_value => unchanged += 1,
+            Some(new_value) => changes.push(KeyChange {
+                kind: ChangeKind::Changed,
+                key,
+                old: Some(old_value),
+                new: Some(new_value),
+            }),
+            None => changes.push(KeyChange {
+                kind: ChangeKind::Removed,
+                key,
+                old: Some(old_value),
+                new: None,
+            }),
+        }
+    }
+    changes.extend(new.into_iter().map(|(key, new_value)| KeyChange {
+        kind: ChangeKind::Added,
+        key,
+        old: None,
+        new: Some(new_value),
+    }));
+    changes.sort_by(|a, b| a.key.cmp(b.key));
+
+    CatalogDiff { changes, unchanged }
+}
+
+fn values_by_key(rows: &[CatalogEntry]) -> BTreeMap<&str, &str> {
+    let mut values = BTreeMap::new();
+    for row in rows {
+        values.entry(row.key.as_str()).or_insert(row.value.as_str());
+    }
+    values
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ChangeKind, KeyChange, diff_catalogs};
+    use binidx_decoder::CatalogEntry;
+
+    fn rows(pairs: &[(&str, &str)]) -> Vec<CatalogEntry> {
+        pairs
+            .iter()
+            .enumerate()
+            .map(|(idx, (key, value))| CatalogEntry {
+                index: idx + 1,
+                key: (*key).to_owned(),
+                value: (*value).to_owned(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn diff_reports_added_removed_and_changed_keys() {
+        let old = rows(&[("N_1", "Sword"), ("N_2", "Shield"), ("N_3", "Helm")]);
+        let new = rows(&[("N_3", "Great Helm"), ("N_1", "Sword"), ("N_4", "Boots")]);
+
+        let diff = diff_catalogs(&old, &new);
+        assert_eq!(diff.unchanged, 1);
+        assert_eq!(
+            diff.changes,
+            [
+                KeyChange {
+                    kind: ChangeKind::Removed,
+                    key: "N_2",
+                    old: Some("Shield"),
+                    new: None,
+                },
+                KeyChange {
+                    kind: ChangeKind::Changed,
+                    key: "N_3",
+                    old: Some("Helm"),
+                    new: Some("Great Helm"),
+                },
+                KeyChange {
+                    kind: ChangeKind::Added,
+                    key: "N_4",
+                    old: None,
+                    new: Some("Boots"),
+                },
+            ]
+        );
+        assert_eq!(diff.count(ChangeKind::Added), 1);
+    }
+
+    #[test]
+    fn diff_uses_first_row_for_repeated_keys() {
+        let old = rows(&[("N_1", "Sword"), ("N_1", "Axe")]);
+        let new = rows(&[("N_1", "Sword")]);
+
+        let diff = diff_catalogs(&old, &new);
+        assert!(diff.changes.is_empty());
+        assert_eq!(diff.unchanged, 1);
+    }
+}
@@ -1,11 +1,24 @@
 #![forbid(unsafe_code)]
 
 use std::io::{self, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use anyhow::Context;
-use binidx_decoder::{CatalogEntry, decode_catalog_from_encoded_tables};
-use clap::{ArgGroup, Parser};
+use binidx_decoder::{
+    CatalogEntry, CatalogIndex, StringCatalog, XorCandidate, decode_catalog_from_encoded_tables,
+    detect_xor_key_for_tables,
+};
+use clap::{ArgGroup, Parser, ValueEnum};
+pub use output::OutputFormat;
+use regex::Regex;
+
+use crate::batch::{MergedCatalog, discover_pairs};
+use crate::diff::diff_catalogs;
+use crate::output::{CatalogOutput, write_catalog, write_diff, write_merged};
+
+mod batch;
+mod diff;
+mod output;
 
 #[derive(Debug, Parser)]
 #[command(
@@ -15,66 +28,209 @@ use clap::{ArgGroup, Parser};
     group(
         ArgGroup::new("action")
             .required(true)
-            .args(["dump", "search_key", "search_value"])
+            .args(["dump", "search_key", "search_value", "detect_xor", "diff", "dir"])
     )
 )]
 pub struct Cli {
     /// Path to the value table (`*.bin`).
-    #[arg(long, short = 'b', value_name = "PATH")]
-    pub bin: PathBuf,
+    #[arg(
+        long,
+        short = 'b',
+        value_name = "PATH",
+        required_unless_present = "dir"
+    )]
+    pub bin: Option<PathBuf>,
 
     /// Path to the index table (`*.idx`).
     #[arg(
         long = "index",
         visible_alias = "idx",
         short = 'i',
-        value_name = "PATH"
+        value_name = "PATH",
+        required_unless_present = "dir"
     )]
-    pub index: PathBuf,
+    pub index: Option<PathBuf>,
 
-    /// XOR key as decimal or hex (for example `50` or `0x32`).
+    /// Decode every `*.bin`/`*.idx` pair in a directory into one catalog
+    /// with a column per language (`name_LANG.bin` pairs with `name.idx`).
+    #[arg(long, value_name = "DIR", conflicts_with_all = ["bin", "index"])]
+    pub dir: Option<PathBuf>,
+
+    /// XOR key as decimal or hex (for example `50` or `0x32`). Detected when omitted.
     #[arg(long, short = 'x', value_parser = parse_u8, value_name = "KEY")]
-    pub xor: u8,
+    pub xor: Option<u8>,
+
+    /// List ranked XOR key candidates instead of decoding rows.
+    #[arg(long)]
+    pub detect_xor: bool,
 
     /// Dump all paired rows.
     #[arg(long)]
     pub dump: bool,
 
-    /// Find rows where key contains query (case-insensitive).
+    /// Find rows where key matches query (see `--match`).
     #[arg(long, short = 'k', value_name = "QUERY")]
     pub search_key: Option<String>,
 
-    /// Find rows where value contains query (case-insensitive).
+    /// Find rows where value matches query (see `--match`).
     #[arg(long, short = 'v', value_name = "QUERY")]
     pub search_value: Option<String>,
+
+    /// How search queries are matched.
+    #[arg(long = "match", short = 'm', value_enum, default_value_t = MatchMode::Contains)]
+    pub match_mode: MatchMode,
+
+    /// Compare against an older table pair and list added, removed and changed keys.
+    #[arg(long, requires_all = ["old_index", "old_bin"])]
+    pub diff: bool,
+
+    /// Index table of the older version for `--diff`.
+    #[arg(long, value_name = "PATH", requires = "diff")]
+    pub old_index: Option<PathBuf>,
+
+    /// Value table of the older version for `--diff`.
+    #[arg(long, value_name = "PATH", requires = "diff")]
+    pub old_bin: Option<PathBuf>,
+
+    /// XOR key for the older tables. Defaults to `--xor`, then detection.
+    #[arg(long, value_parser = parse_u8, value_name = "KEY", requires = "diff")]
+    pub old_xor: Option<u8>,
+
+    /// Output format for selected rows.
+    #[arg(long, short = 'f', value_enum, default_value_t = OutputFormat::Tsv)]
+    pub format: OutputFormat,
+}
+
+/// Query interpretation for `--search-key` and `--search-value`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum MatchMode {
+    /// Substring match, ignoring case.
+    #[default]
+    Contains,
+    /// Whole-string match, case-sensitive.
+    Exact,
+    /// Starts-with match, case-sensitive.
+    Prefix,
+    /// Regular expression; use `(?i)` to ignore case.
+    Regex,
 }
 
 pub fn run(cli: Cli) -> anyhow::Result<()> {
-    let idx = std::fs::read(&cli.index)
-        .with_context(|| format!("failed to read index table {}", cli.index.display()))?;
-    let bin = std::fs::read(&cli.bin)
-        .with_context(|| format!("failed to read value table {}", cli.bin.display()))?;
-
-    let catalog = decode_catalog_from_encoded_tables(&idx, &bin, cli.xor).with_context(|| {
-        format!(
-            "failed to decode {} + {}",
-            cli.index.display(),
-            cli.bin.display()
-        )
-    })?;
+    if let Some(dir) = &cli.dir {
+        let discovered = discover_pairs(dir)?;
+        for bin in &discovered.skipped {
+            eprintln!("skipping {}: no matching *.idx table", bin.display());
+        }
+        anyhow::ensure!(
+            !discovered.pairs.is_empty(),
+            "no *.bin/*.idx table pairs found in {}",
+            dir.display()
+        );
+
+        let mut merged = MergedCatalog {
+            skipped: discovered.skipped,
+            ..MergedCatalog::default()
+        };
+        for pair in &discovered.pairs {
+            let (xor, catalog) = read_catalog(&pair.index, &pair.bin, cli.xor)?;
+            merged.insert(pair, xor, &catalog.rows);
+        }
+
+        let mut stdout = io::stdout().lock();
+        write_merged(&mut stdout, cli.format, &merged)?;
+        return Ok(());
+    }
+
+    let index = cli.index.as_deref().context("--index is required")?;
+    let bin = cli.bin.as_deref().context("--bin is required")?;
+
+    if cli.detect_xor {
+        let (idx, bin) = read_tables(index, bin)?;
+        let mut stdout = io::stdout().lock();
+        write_candidates_tsv(&mut stdout, &detect_xor_key_for_tables(&[&idx, &bin]))?;
+        return Ok(());
+    }
+
+    let (xor, catalog) = read_catalog(index, bin, cli.xor)?;
+
+    if cli.diff {
+        let old_index = cli
+            .old_index
+            .as_deref()
+            .context("--diff requires --old-index")?;
+        let old_bin = cli
+            .old_bin
+            .as_deref()
+            .context("--diff requires --old-bin")?;
+        let (_, old_catalog) = read_catalog(old_index, old_bin, cli.old_xor.or(cli.xor))?;
+
+        let mut stdout = io::stdout().lock();
+        write_diff(
+            &mut stdout,
+            cli.format,
+            &diff_catalogs(&old_catalog.rows, &catalog.rows),
+        )?;
+        return Ok(());
+    }
 
     let rows = select_rows(
-        &catalog.rows,
+        &catalog.index(),
         cli.dump,
+        cli.match_mode,
         cli.search_key.as_deref(),
         cli.search_value.as_deref(),
-    );
+    )?;
 
     let mut stdout = io::stdout().lock();
-    write_rows_tsv(&mut stdout, &rows)?;
+    write_catalog(
+        &mut stdout,
+        cli.format,
+        &CatalogOutput {
+            xor,
+            catalog: &catalog,
+            rows: &rows,
+        },
+    )?;
     Ok(())
 }
 
+fn read_tables(index: &Path, bin: &Path) -> anyhow::Result<(Vec<u8>, Vec<u8>)> {
+    let idx = std::fs::read(index)
+        .with_context(|| format!("failed to read index table {}", index.display()))?;
+    let bin = std::fs::read(bin)
+        .with_context(|| format!("failed to read value table {}", bin.display()))?;
+    Ok((idx, bin))
+}
+
+/// Read and decode a table pair, detecting the XOR key when none is given.
+fn read_catalog(index: &Path, bin: &Path, xor: Option<u8>) -> anyhow::Result<(u8, StringCatalog)> {
+    let (idx_bytes, bin_bytes) = read_tables(index, bin)?;
+    let xor = match xor {
+        Some(xor) => xor,
+        None => {
+            let candidates = detect_xor_key_for_tables(&[&idx_bytes, &bin_bytes]);
+            let best = candidates.first().with_context(|| {
+                format!(
+                    "could not detect the XOR key for {}; pass --xor",
+                    bin.display()
+                )
+            })?;
+            eprintln!(
+                "detected XOR key 0x{:02x} for {} ({} entries, {} trailer bytes)",
+                best.key,
+                bin.display(),
+                best.entries,
+                best.trailer_len
+            );
+            best.key
+        }
+    };
+
+    let catalog = decode_catalog_from_encoded_tables(&idx_bytes, &bin_bytes, xor)
+        .with_context(|| format!("failed to decode {} + {}", index.display(), bin.display()))?;
+    Ok((xor, catalog))
+}
+
 fn parse_u8(input: &str) -> Result<u8, String> {
     if let Some(hex) = input
         .strip_prefix("0x")
@@ -87,70 +243,59 @@ fn parse_u8(input: &str) -> Result<u8, String> {
 }
 
 fn select_rows<'a>(
-    rows: &'a [CatalogEntry],
+    index: &CatalogIndex<'a>,
     dump: bool,
+    mode: MatchMode,
     search_key: Option<&str>,
     search_value: Option<&str>,
-) -> Vec<&'a CatalogEntry> {
-    rows.iter()
-        .filter(|row| {
-            if dump {
-                return true;
-            }
-
-            let key_match = search_key
-                .map(|query| contains_case_insensitive(&row.key, query))
-                .unwrap_or(false);
-            let value_match = search_value
-                .map(|query| contains_case_insensitive(&row.value, query))
-                .unwrap_or(false);
-
-            key_match || value_match
-        })
-        .collect()
-}
+) -> anyhow::Result<Vec<&'a CatalogEntry>> {
+    if dump {
+        return Ok(index.rows().iter().collect());
+    }
 
-fn contains_case_insensitive(haystack: &str, needle: &str) -> bool {
-    if needle.is_empty() {
-        return true;
+    let mut rows = Vec::new();
+    if let Some(query) = search_key {
+        rows.extend(match mode {
+            MatchMode::Exact => index.get_all(query),
+            MatchMode::Prefix => index.keys_with_prefix(query),
+            MatchMode::Contains | MatchMode::Regex => index.search_keys(&query_regex(mode, query)?),
+        });
+    }
+    if let Some(query) = search_value {
+        rows.extend(index.search_values(&query_regex(mode, query)?));
     }
 
-    haystack.to_lowercase().contains(&needle.to_lowercase())
+    rows.sort_by_key(|row| row.index);
+    rows.dedup_by_key(|row| row.index);
+    Ok(rows)
 }
 
-fn write_rows_tsv<W: Write>(writer: &mut W, rows: &[&CatalogEntry]) -> io::Result<()> {
-    for row in rows {
+fn query_regex(mode: MatchMode, query: &str) -> anyhow::Result<Regex> {
+    let pattern = match mode {
+        MatchMode::Contains => format!("(?i){}", regex::escape(query)),
+        MatchMode::Exact => format!("^{}$", regex::escape(query)),
+        MatchMode::Prefix => format!("^{}", regex::escape(query)),
+        MatchMode::Regex => query.to_owned(),
+    };
+    Regex::new(&pattern).with_context(|| format!("invalid search pattern {query:?}"))
+}
+
+fn write_candidates_tsv<W: Write>(writer: &mut W, candidates: &[XorCandidate]) -> io::Result<()> {
+    for candidate in candidates {
         writeln!(
             writer,
-            "{}\t{}\t{}",
-            row.index,
-            sanitize_tsv_field(&row.key),
-            sanitize_tsv_field(&row.value)
+            "0x{:02x}\t{}\t{}",
+            candidate.key, candidate.entries, candidate.trailer_len
         )?;
     }
 
     Ok(())
 }
 
-fn sanitize_tsv_field(value: &str) -> String {
-    let mut output = String::with_capacity(value.len());
-    for ch in value.chars() {
-        match ch {
-            '\n' => output.push_str("\\n"),
-            '\r' => output.push_str("\\r"),
-            '\t' => output.push_str("\\t"),
-            _ if ch.is_control() => output.extend(ch.escape_default()),
-            _ => output.push(ch),
-        }
-    }
-
-    output
-}
-
 #[cfg(test)]
 mod tests {
-    use super::{Cli, contains_case_insensitive, select_rows, write_rows_tsv};
-    use binidx_decoder::CatalogEntry;
+    use super::{Cli, MatchMode, OutputFormat, select_rows, write_candidates_tsv};
+    use binidx_decoder::{CatalogEntry, CatalogIndex, XorCandidate};
     use clap::Parser;
 
     fn row(index: usize, key: &str, value: &str) -> CatalogEntry {
@@ -184,9 +329,47 @@ mod tests {
         .expect("parse args");
 
         assert!(cli.dump);
-        assert_eq!(cli.index, PathBuf::from("table.idx"));
-        assert_eq!(cli.bin, PathBuf::from("table_en.bin"));
-        assert_eq!(cli.xor, 0x32);
+        assert_eq!(cli.match_mode, MatchMode::Contains);
+        assert_eq!(cli.index, Some(PathBuf::from("table.idx")));
+        assert_eq!(cli.bin, Some(PathBuf::from("table_en.bin")));
+        assert_eq!(cli.xor, Some(0x32));
+        assert_eq!(cli.format, OutputFormat::Tsv);
+    }
+
+    #[test]
+    fn cli_accepts_structured_formats_and_match_modes() {
+        let cli = Cli::try_parse_from([
+            "binidx-cli",
+            "-i",
+            "table.idx",
+            "-b",
+            "table_en.bin",
+            "--dump",
+            "--format",
+            "ndjson",
+            "--match",
+            "regex",
+        ])
+        .expect("parse args");
+
+        assert_eq!(cli.format, OutputFormat::Ndjson);
+        assert_eq!(cli.match_mode, MatchMode::Regex);
+    }
+
+    #[test]
+    fn cli_allows_omitting_xor_and_detect_action() {
+        let cli = Cli::try_parse_from([
+            "binidx-cli",
+            "--index",
+            "table.idx",
+            "--bin",
+            "table_en.bin",
+            "--detect-xor",
+        ])
+        .expect("parse args");
+
+        assert!(cli.detect_xor);
+        assert_eq!(cli.xor, None);
     }
 
     use std::path::PathBuf;
@@ -211,44 +394,137 @@ mod tests {
     }
 
     #[test]
-    fn case_insensitive_substring_matching_works() {
-        assert!(contains_case_insensitive("Sacred Dominion", "dom"));
-        assert!(contains_case_insensitive("Sacred Dominion", "DOM"));
-        assert!(!contains_case_insensitive("Sacred Dominion", "helm"));
+    fn cli_dir_mode_replaces_table_paths() {
+        let cli = Cli::try_parse_from(["binidx-cli", "--dir", "tables", "-f", "csv"])
+            .expect("parse args");
+        assert_eq!(cli.dir, Some(PathBuf::from("tables")));
+        assert_eq!(cli.index, None);
+
+        let mixed = Cli::try_parse_from(["binidx-cli", "--dir", "tables", "-b", "table_en.bin"]);
+        assert!(mixed.is_err());
+        let missing = Cli::try_parse_from(["binidx-cli", "--dump"]);
+        assert!(missing.is_err());
+    }
+
+    #[test]
+    fn cli_diff_requires_old_tables() {
+        let base = ["binidx-cli", "-i", "new.idx", "-b", "new_en.bin", "--diff"];
+        let error = Cli::try_parse_from(base).expect_err("must fail");
+        assert_eq!(
+            error.kind(),
+            clap::error::ErrorKind::MissingRequiredArgument
+        );
+
+        let cli = Cli::try_parse_from(base.into_iter().chain([
+            "--old-index",
+            "old.idx",
+            "--old-bin",
+            "old_en.bin",
+        ]))
+        .expect("parse args");
+        assert!(cli.diff);
+        assert_eq!(cli.old_index, Some(PathBuf::from("old.idx")));
+        assert_eq!(cli.old_xor, None);
+
+        let orphan = Cli::try_parse_from([
+            "binidx-cli",
+            "-i",
+            "new.idx",
+            "-b",
+            "new_en.bin",
+            "--dump",
+            "--old-bin",
+            "old_en.bin",
+        ]);
+        assert!(orphan.is_err());
+    }
+
+    fn select(
+        rows: &[CatalogEntry],
+        mode: MatchMode,
+        search_key: Option<&str>,
+        search_value: Option<&str>,
+    ) -> Vec<usize> {
+        select_rows(
+            &CatalogIndex::new(rows),
+            false,
+            mode,
+            search_key,
+            search_value,
+        )
+        .expect("select rows")
+        .into_iter()
+        .map(|row| row.index)
+        .collect()
     }
 
     #[test]
     fn select_rows_supports_dump_and_search_modes() {
         let rows = sample_rows();
+        let index = CatalogIndex::new(&rows);
 
-        let dump_rows = select_rows(&rows, true, None, None);
+        let dump_rows =
+            select_rows(&index, true, MatchMode::Contains, None, None).expect("select rows");
         assert_eq!(dump_rows.len(), 3);
 
-        let key_rows = select_rows(&rows, false, Some("d_"), None);
-        assert_eq!(key_rows.len(), 1);
-        assert_eq!(key_rows[0].key, "D_1");
+        assert_eq!(select(&rows, MatchMode::Contains, Some("d_"), None), [2]);
+        assert_eq!(
+            select(&rows, MatchMode::Contains, None, Some("shield")),
+            [3]
+        );
+        assert_eq!(
+            select(&rows, MatchMode::Contains, Some("N_1"), Some("shield")),
+            [1, 3]
+        );
+        assert_eq!(
+            select(&rows, MatchMode::Contains, Some(""), None),
+            [1, 2, 3]
+        );
+    }
 
-        let value_rows = select_rows(&rows, false, None, Some("shield"));
-        assert_eq!(value_rows.len(), 1);
-        assert_eq!(value_rows[0].value, "Shield");
+    #[test]
+    fn select_rows_supports_exact_prefix_and_regex_modes() {
+        let rows = sample_rows();
+
+        assert_eq!(select(&rows, MatchMode::Exact, Some("N_1"), None), [1]);
+        assert!(select(&rows, MatchMode::Exact, Some("n_1"), None).is_empty());
+        assert_eq!(select(&rows, MatchMode::Exact, None, Some("Shield")), [3]);
+        assert_eq!(select(&rows, MatchMode::Prefix, Some("N_"), None), [1, 3]);
+        assert_eq!(select(&rows, MatchMode::Prefix, None, Some("Pri")), [2]);
+        assert_eq!(
+            select(&rows, MatchMode::Regex, Some(r"^[DN]_1$"), Some("(?i)^shi")),
+            [1, 2, 3]
+        );
+        assert_eq!(
+            select(&rows, MatchMode::Regex, Some("N_1"), Some("Sacred")),
+            [1]
+        );
+    }
 
-        let either_rows = select_rows(&rows, false, Some("N_1"), Some("shield"));
-        assert_eq!(either_rows.len(), 2);
-        assert_eq!(either_rows[0].index, 1);
-        assert_eq!(either_rows[1].index, 3);
+    #[test]
+    fn select_rows_rejects_invalid_regex() {
+        let rows = sample_rows();
+        let result = select_rows(
+            &CatalogIndex::new(&rows),
+            false,
+            MatchMode::Regex,
+            Some("("),
+            None,
+        );
+        assert!(result.is_err());
     }
 
     #[test]
-    fn tsv_output_sanitizes_control_characters() {
-        let rows = [row(1, "N\t1", "line1\nline2")];
-        let refs = rows.iter().collect::<Vec<_>>();
+    fn candidates_tsv_lists_hex_keys() {
+        let candidates = [XorCandidate {
+            key: 0x32,
+            entries: 4,
+            trailer_len: 0,
+        }];
         let mut output = Vec::new();
 
-        write_rows_tsv(&mut output, &refs).expect("write tsv");
+        write_candidates_tsv(&mut output, &candidates).expect("write tsv");
 
-        assert_eq!(
-            String::from_utf8(output).expect("utf8"),
-            "1\tN\\t1\tline1\\nline2\n"
-        );
+        assert_eq!(String::from_utf8(output).expect("utf8"), "0x32\t4\t0\n");
     }
 }
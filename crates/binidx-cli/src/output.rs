@@ -0,0 +1,580 @@
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+
+use binidx_decoder::{CatalogEntry, StringCatalog, StringEntry, StringTable};
+use clap::ValueEnum;
+use serde_json::{Value, json};
+
+use crate::batch::{MergedCatalog, MergedTable};
+use crate::diff::{CatalogDiff, ChangeKind, KeyChange};
+
+/// Output encodings for selected catalog rows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum OutputFormat {
+    /// Tab-separated `index`, `key`, `value` rows.
+    #[default]
+    Tsv,
+    /// One JSON document with rows, extras and table metadata.
+    Json,
+    /// One JSON record per line: metadata, rows, then extras.
+    Ndjson,
+    /// CSV rows and extras with a `kind,index,key,value` header.
+    Csv,
+    /// One YAML document with rows, extras and table metadata.
+    Yaml,
+}
+
+/// Selected rows together with the catalog they came from.
+pub(crate) struct CatalogOutput<'a> {
+    pub(crate) xor: u8,
+    pub(crate) catalog: &'a StringCatalog,
+    pub(crate) rows: &'a [&'a CatalogEntry],
+}
+
+pub(crate) fn write_catalog<W: Write>(
+    writer: &mut W,
+    format: OutputFormat,
+    output: &CatalogOutput<'_>,
+) -> anyhow::Result<()> {
+    match format {
+        OutputFormat::Tsv => write_rows_tsv(writer, output.rows)?,
+        OutputFormat::Json => {
+            serde_json::to_writer_pretty(&mut *writer, &catalog_document(output))?;
+            writeln!(writer)?;
+        }
+        OutputFormat::Ndjson => write_catalog_ndjson(writer, output)?,
+        OutputFormat::Csv => write_catalog_csv(writer, output)?,
+        OutputFormat::Yaml => serde_yaml::to_writer(&mut *writer, &catalog_document(output))?,
+    }
+
+    Ok(())
+}
+
+pub(crate) fn write_rows_tsv<W: Write>(writer: &mut W, rows: &[&CatalogEntry]) -> io::Result<()> {
+    for row in rows {
+        writeln!(
+            writer,
+            "{}\t{}\t{}",
+            row.index,
+            sanitize_tsv_field(&row.key),
+            sanitize_tsv_field(&row.value)
+        )?;
+    }
+
+    Ok(())
+}
+
+fn sanitize_tsv_field(value: &str) -> String {
+    let mut output = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\t' => output.push_str("\\t"),
+            _ if ch.is_control() => output.extend(ch.escape_default()),
+            _ => output.push(ch),
+        }
+    }
+
+    output
+}
+
+pub(crate) fn write_diff<W: Write>(
+    writer: &mut W,
+    format: OutputFormat,
+    diff: &CatalogDiff<'_>,
+) -> anyhow::Result<()> {
+    match format {
+        OutputFormat::Tsv => {
+            for change in &diff.changes {
+                writeln!(
+                    writer,
+                    "{}\t{}\t{}\t{}",
+                    change.kind.as_str(),
+                    sanitize_tsv_field(change.key),
+                    sanitize_tsv_field(change.old.unwrap_or_default()),
+                    sanitize_tsv_field(change.new.unwrap_or_default())
+                )?;
+            }
+        }
+        OutputFormat::Json => {
+            serde_json::to_writer_pretty(&mut *writer, &diff_document(diff))?;
+            writeln!(writer)?;
+        }
+        OutputFormat::Ndjson => {
+            serde_json::to_writer(
+                &mut *writer,
+                &json!({ "kind": "summary", "summary": diff_summary(diff) }),
+            )?;
+            writeln!(writer)?;
+            for change in &diff.changes {
+                serde_json::to_writer(
+                    &mut *writer,
+                    &tagged(change.kind.as_str(), change_value(change)),
+                )?;
+                writeln!(writer)?;
+            }
+        }
+        OutputFormat::Csv => {
+            let mut csv = csv::Writer::from_writer(writer);
+            csv.write_record(["kind", "key", "old", "new"])?;
+            for change in &diff.changes {
+                csv.write_record([
+                    change.kind.as_str(),
+                    change.key,
+                    change.old.unwrap_or_default(),
+                    change.new.unwrap_or_default(),
+                ])?;
+            }
+            csv.flush()?;
+        }
+        OutputFormat::Yaml => serde_yaml::to_writer(&mut *writer, &diff_document(diff))?,
+    }
+
+    Ok(())
+}
+
+/// TSV and CSV write one column per language; missing translations are empty.
+pub(crate) fn write_merged<W: Write>(
+    writer: &mut W,
+    format: OutputFormat,
+    merged: &MergedCatalog,
+) -> anyhow::Result<()> {
+    match format {
+        OutputFormat::Tsv => {
+            let header = std::iter::once("key")
+                .chain(merged.languages.iter().map(String::as_str))
+                .map(sanitize_tsv_field)
+                .collect::<Vec<_>>();
+            writeln!(writer, "{}", header.join("\t"))?;
+            for (key, values) in &merged.strings {
+                let row = std::iter::once(key.as_str())
+                    .chain(language_cells(merged, values))
+                    .map(sanitize_tsv_field)
+                    .collect::<Vec<_>>();
+                writeln!(writer, "{}", row.join("\t"))?;
+            }
+        }
+        OutputFormat::Json => {
+            serde_json::to_writer_pretty(&mut *writer, &merged_document(merged))?;
+            writeln!(writer)?;
+        }
+        OutputFormat::Ndjson => {
+            serde_json::to_writer(
+                &mut *writer,
+                &json!({
+                    "kind": "metadata",
+                    "languages": merged.languages,
+                    "tables": merged.tables.iter().map(merged_table_value).collect::<Vec<_>>(),
+                    "skipped": skipped_paths(merged),
+                }),
+            )?;
+            writeln!(writer)?;
+            for (key, values) in &merged.strings {
+                serde_json::to_writer(
+                    &mut *writer,
+                    &json!({ "kind": "string", "key": key, "values": values }),
+                )?;
+                writeln!(writer)?;
+            }
+        }
+        OutputFormat::Csv => {
+            let mut csv = csv::Writer::from_writer(writer);
+            csv.write_record(
+                std::iter::once("key").chain(merged.languages.iter().map(String::as_str)),
+            )?;
+            for (key, values) in &merged.strings {
+                csv.write_record(
+                    std::iter::once(key.as_str()).chain(language_cells(merged, values)),
+                )?;
+            }
+            csv.flush()?;
+        }
+        OutputFormat::Yaml => serde_yaml::to_writer(&mut *writer, &merged_document(merged))?,
+    }
+
+    Ok(())
+}
+
+fn language_cells<'a>(
+    merged: &'a MergedCatalog,
+    values: &'a BTreeMap<String, String>,
+) -> Vec<&'a str> {
+    merged
+        .languages
+        .iter()
+        .map(|language| values.get(language).map(String::as_str).unwrap_or_default())
+        .collect()
+}
+
+fn merged_document(merged: &MergedCatalog) -> Value {
+    json!({
+        "languages": merged.languages,
+        "tables": merged.tables.iter().map(merged_table_value).collect::<Vec<_>>(),
+        "skipped": skipped_paths(merged),
+        "strings": merged.strings,
+    })
+}
+
+fn skipped_paths(merged: &MergedCatalog) -> Vec<String> {
+    merged
+        .skipped
+        .iter()
+        .map(|path| path.display().to_string())
+        .collect()
+}
+
+fn merged_table_value(table: &MergedTable) -> Value {
+    json!({
+        "language": table.pair.language,
+        "index": table.pair.index.display().to_string(),
+        "bin": table.pair.bin.display().to_string(),
+        "xor": table.xor,
+        "rows": table.rows,
+    })
+}
+
+fn diff_document(diff: &CatalogDiff<'_>) -> Value {
+    let changes_of = |kind: ChangeKind| {
+        diff.changes
+            .iter()
+            .filter(|change| change.kind == kind)
+            .map(change_value)
+            .collect::<Vec<_>>()
+    };
+
+    json!({
+        "summary": diff_summary(diff),
+        "added": changes_of(ChangeKind::Added),
+        "removed": changes_of(ChangeKind::Removed),
+        "changed": changes_of(ChangeKind::Changed),
+    })
+}
+
+fn diff_summary(diff: &CatalogDiff<'_>) -> Value {
+    json!({
+        "added": diff.count(ChangeKind::Added),
+        "removed": diff.count(ChangeKind::Removed),
+        "changed": diff.count(ChangeKind::Changed),
+        "unchanged": diff.unchanged,
+    })
+}
+
+fn change_value(change: &KeyChange<'_>) -> Value {
+    match change.kind {
+        ChangeKind::Added => json!({ "key": change.key, "value": change.new }),
+        ChangeKind::Removed => json!({ "key": change.key, "value": change.old }),
+        ChangeKind::Changed => json!({ "key": change.key, "old": change.old, "new": change.new }),
+    }
+}
+
+fn catalog_document(output: &CatalogOutput<'_>) -> Value {
+    json!({
+        "metadata": metadata(output),
+        "rows": output.rows.iter().map(|row| row_value(row)).collect::<Vec<_>>(),
+        "extra_keys": output.catalog.extra_keys.iter().map(entry_value).collect::<Vec<_>>(),
+        "extra_values": output.catalog.extra_values.iter().map(entry_value).collect::<Vec<_>>(),
+    })
+}
+
+fn write_catalog_ndjson<W: Write>(
+    writer: &mut W,
+    output: &CatalogOutput<'_>,
+) -> anyhow::Result<()> {
+    let mut records = vec![json!({ "kind": "metadata", "metadata": metadata(output) })];
+    records.extend(output.rows.iter().map(|row| tagged("row", row_value(row))));
+    records.extend(
+        output
+            .catalog
+            .extra_keys
+            .iter()
+            .map(|entry| tagged("extra_key", entry_value(entry))),
+    );
+    records.extend(
+        output
+            .catalog
+            .extra_values
+            .iter()
+            .map(|entry| tagged("extra_value", entry_value(entry))),
+    );
+
+    for record in records {
+        serde_json::to_writer(&mut *writer, &record)?;
+        writeln!(writer)?;
+    }
+
+    Ok(())
+}
+
+/// CSV carries rows and extras only; table metadata needs JSON, NDJSON or YAML.
+fn write_catalog_csv<W: Write>(writer: &mut W, output: &CatalogOutput<'_>) -> anyhow::Result<()> {
+    let mut csv = csv::Writer::from_writer(writer);
+    csv.write_record(["kind", "index", "key", "value"])?;
+    for row in output.rows {
+        csv.write_record(["row", &row.index.to_string(), &row.key, &row.value])?;
+    }
+    for entry in &output.catalog.extra_keys {
+        csv.write_record(["extra_key", &entry.index.to_string(), &entry.value, ""])?;
+    }
+    for entry in &output.catalog.extra_values {
+        csv.write_record(["extra_value", &entry.index.to_string(), "", &entry.value])?;
+    }
+    csv.flush()?;
+
+    Ok(())
+}
+
+fn metadata(output: &CatalogOutput<'_>) -> Value {
+    json!({
+        "xor": output.xor,
+        "rows": output.catalog.rows.len(),
+        "selected_rows": output.rows.len(),
+        "index_table": table_metadata(&output.catalog.keys),
+        "value_table": table_metadata(&output.catalog.values),
+    })
+}
+
+fn table_metadata(table: &StringTable) -> Value {
+    json!({
+        "header": hex(&table.header),
+        "entries": table.entries.len(),
+        "trailer_len": table.trailer.len(),
+        "trailer": hex(&table.trailer),
+    })
+}
+
+fn row_value(row: &CatalogEntry) -> Value {
+    json!({ "index": row.index, "key": row.key, "value": row.value })
+}
+
+fn entry_value(entry: &StringEntry) -> Value {
+    json!({ "index": entry.index, "offset": entry.offset, "value": entry.value })
+}
+
+fn tagged(kind: &str, mut value: Value) -> Value {
+    if let Value::Object(map) = &mut value {
+        map.insert("kind".to_owned(), Value::String(kind.to_owned()));
+    }
+    value
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        CatalogOutput, OutputFormat, write_catalog, write_diff, write_merged, write_rows_tsv,
+    };
+    use crate::batch::{MergedCatalog, TablePair};
+    use crate::diff::diff_catalogs;
+    use binidx_decoder::{CatalogEntry, decode_catalog_from_decoded_tables};
+    use serde_json::{Value, json};
+
+    fn table_with_strings(strings: &[&str], trailer: &[u8]) -> Vec<u8> {
+        let mut bytes = vec![0x78, 0x00];
+        for value in strings {
+            bytes.extend_from_slice(&(value.len() as u16).to_le_bytes());
+            bytes.extend_from_slice(value.as_bytes());
+        }
+        bytes.extend_from_slice(trailer);
+        bytes
+    }
+
+    fn render(format: OutputFormat) -> String {
+        let idx = table_with_strings(&["N_1", "N_2", "N_3"], &[0xff]);
+        let bin = table_with_strings(&["Sword, \"big\"", "Shield"], &[]);
+        let catalog = decode_catalog_from_decoded_tables(&idx, &bin).expect("decode catalog");
+        let rows = catalog.rows.iter().collect::<Vec<_>>();
+        let output = CatalogOutput {
+            xor: 0x32,
+            catalog: &catalog,
+            rows: &rows,
+        };
+        let mut buffer = Vec::new();
+        write_catalog(&mut buffer, format, &output).expect("write catalog");
+        String::from_utf8(buffer).expect("utf8")
+    }
+
+    #[test]
+    fn tsv_output_sanitizes_control_characters() {
+        let rows = [CatalogEntry {
+            index: 1,
+            key: "N\t1".to_owned(),
+            value: "line1\nline2".to_owned(),
+        }];
+        let refs = rows.iter().collect::<Vec<_>>();
+        let mut output = Vec::new();
+
+        write_rows_tsv(&mut output, &refs).expect("write tsv");
+
+        assert_eq!(
+            String::from_utf8(output).expect("utf8"),
+            "1\tN\\t1\tline1\\nline2\n"
+        );
+    }
+
+    #[test]
+    fn json_output_includes_extras_and_metadata() {
+        let document: Value = serde_json::from_str(&render(OutputFormat::Json)).expect("json");
+
+        assert_eq!(document["metadata"]["xor"], json!(0x32));
+        assert_eq!(
+            document["metadata"]["index_table"],
+            json!({ "header": "7800", "entries": 3, "trailer_len": 1, "trailer": "ff" })
+        );
+        assert_eq!(
+            document["rows"][0],
+            json!({ "index": 1, "key": "N_1", "value": "Sword, \"big\"" })
+        );
+        assert_eq!(
+            document["extra_keys"],
+            json!([{ "index": 3, "offset": 12, "value": "N_3" }])
+        );
+        assert_eq!(document["extra_values"], json!([]));
+    }
+
+    #[test]
+    fn yaml_output_matches_json_document() {
+        let yaml: Value = serde_yaml::from_str(&render(OutputFormat::Yaml)).expect("yaml");
+        let json: Value = serde_json::from_str(&render(OutputFormat::Json)).expect("json");
+        assert_eq!(yaml, json);
+    }
+
+    #[test]
+    fn ndjson_output_tags_each_record() {
+        let kinds = render(OutputFormat::Ndjson)
+            .lines()
+            .map(|line| {
+                let record: Value = serde_json::from_str(line).expect("json line");
+                record["kind"].as_str().expect("kind").to_owned()
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(kinds, ["metadata", "row", "row", "extra_key"]);
+    }
+
+    #[test]
+    fn csv_output_quotes_fields() {
+        assert_eq!(
+            render(OutputFormat::Csv),
+            "kind,index,key,value\nrow,1,N_1,\"Sword, \"\"big\"\"\"\nrow,2,N_2,Shield\nextra_key,3,N_3,\n"
+        );
+    }
+
+    fn render_diff(format: OutputFormat) -> String {
+        let row = |index, key: &str, value: &str| CatalogEntry {
+            index,
+            key: key.to_owned(),
+            value: value.to_owned(),
+        };
+        let old = [row(1, "N_1", "Sword"), row(2, "N_2", "Shield")];
+        let new = [row(1, "N_1", "Long\tSword"), row(2, "N_3", "Helm")];
+        let mut buffer = Vec::new();
+        write_diff(&mut buffer, format, &diff_catalogs(&old, &new)).expect("write diff");
+        String::from_utf8(buffer).expect("utf8")
+    }
+
+    #[test]
+    fn diff_tsv_lists_changes_by_key() {
+        assert_eq!(
+            render_diff(OutputFormat::Tsv),
+            "changed\tN_1\tSword\tLong\\tSword\nremoved\tN_2\tShield\t\nadded\tN_3\t\tHelm\n"
+        );
+    }
+
+    #[test]
+    fn diff_json_groups_changes_by_kind() {
+        let document: Value = serde_json::from_str(&render_diff(OutputFormat::Json)).expect("json");
+
+        assert_eq!(
+            document["summary"],
+            json!({ "added": 1, "removed": 1, "changed": 1, "unchanged": 0 })
+        );
+        assert_eq!(
+            document["added"],
+            json!([{ "key": "N_3", "value": "Helm" }])
+        );
+        assert_eq!(
+            document["changed"],
+            json!([{ "key": "N_1", "old": "Sword", "new": "Long\tSword" }])
+        );
+    }
+
+    #[test]
+    fn diff_ndjson_and_csv_tag_each_change() {
+        let kinds = render_diff(OutputFormat::Ndjson)
+            .lines()
+            .map(|line| {
+                let record: Value = serde_json::from_str(line).expect("json line");
+                record["kind"].as_str().expect("kind").to_owned()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(kinds, ["summary", "changed", "removed", "added"]);
+
+        assert_eq!(
+            render_diff(OutputFormat::Csv).lines().nth(3),
+            Some("added,N_3,,Helm")
+        );
+    }
+
+    fn render_merged(format: OutputFormat) -> String {
+        let row = |key: &str, value: &str| CatalogEntry {
+            index: 1,
+            key: key.to_owned(),
+            value: value.to_owned(),
+        };
+        let pair = |language: &str| TablePair {
+            language: language.to_owned(),
+            index: "table.idx".into(),
+            bin: format!("table_{language}.bin").into(),
+        };
+        let mut merged = MergedCatalog::default();
+        merged.insert(
+            &pair("en"),
+            0x32,
+            &[row("N_1", "Sword"), row("N_2", "Shield")],
+        );
+        merged.insert(&pair("de"), 0x32, &[row("N_1", "Schwert, groß")]);
+        merged.skipped.push("orphan_fr.bin".into());
+
+        let mut buffer = Vec::new();
+        write_merged(&mut buffer, format, &merged).expect("write merged");
+        String::from_utf8(buffer).expect("utf8")
+    }
+
+    #[test]
+    fn merged_tables_have_a_column_per_language() {
+        assert_eq!(
+            render_merged(OutputFormat::Tsv),
+            "key\tde\ten\nN_1\tSchwert, groß\tSword\nN_2\t\tShield\n"
+        );
+        assert_eq!(
+            render_merged(OutputFormat::Csv),
+            "key,de,en\nN_1,\"Schwert, groß\",Sword\nN_2,,Shield\n"
+        );
+    }
+
+    #[test]
+    fn merged_json_includes_tables_and_strings() {
+        let document: Value =
+            serde_json::from_str(&render_merged(OutputFormat::Json)).expect("json");
+
+        assert_eq!(document["languages"], json!(["de", "en"]));
+        assert_eq!(document["tables"][1]["bin"], json!("table_de.bin"));
+        assert_eq!(document["skipped"], json!(["orphan_fr.bin"]));
+        assert_eq!(document["strings"]["N_2"], json!({ "en": "Shield" }));
+
+        let records = render_merged(OutputFormat::Ndjson)
+            .lines()
+            .map(|line| serde_json::from_str::<Value>(line).expect("json line"))
+            .collect::<Vec<_>>();
+        assert_eq!(records[0]["skipped"], json!(["orphan_fr.bin"]));
+        let last = records.last().expect("records");
+        assert_eq!(
+            *last,
+            json!({ "kind": "string", "key": "N_2", "values": { "en": "Shield" } })
+        );
+    }
+}
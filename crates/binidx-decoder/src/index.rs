@@ -0,0 +1,157 @@
+use std::collections::HashMap;
+
+use regex::Regex;
+
+use crate::{CatalogEntry, StringCatalog};
+
+/// Lookup index over catalog rows.
+///
+/// Exact key lookups use a hash map and prefix searches a sorted key list.
+/// Every query returns matching rows in catalog order.
+#[derive(Debug, Clone)]
+pub struct CatalogIndex<'a> {
+    rows: &'a [CatalogEntry],
+    by_key: HashMap<&'a str, Vec<usize>>,
+    sorted_keys: Vec<usize>,
+}
+
+impl<'a> CatalogIndex<'a> {
+    /// Index rows by key.
+    pub fn new(rows: &'a [CatalogEntry]) -> Self {
+        let mut by_key = HashMap::<&str, Vec<usize>>::with_capacity(rows.len());
+        for (position, row) in rows.iter().enumerate() {
+            by_key.entry(row.key.as_str()).or_default().push(position);
+        }
+
+        let mut sorted_keys = (0..rows.len()).collect::<Vec<_>>();
+        sorted_keys.sort_by(|&a, &b| rows[a].key.cmp(&rows[b].key).then(a.cmp(&b)));
+
+        Self {
+            rows,
+            by_key,
+            sorted_keys,
+        }
+    }
+
+    /// Indexed rows.
+    pub fn rows(&self) -> &'a [CatalogEntry] {
+        self.rows
+    }
+
+    /// First row with exactly this key.
+    pub fn get(&self, key: &str) -> Option<&'a CatalogEntry> {
+        let position = *self.by_key.get(key)?.first()?;
+        Some(&self.rows[position])
+    }
+
+    /// Every row with exactly this key. Keys can repeat in game tables.
+    pub fn get_all(&self, key: &str) -> Vec<&'a CatalogEntry> {
+        self.by_key
+            .get(key)
+            .map(|positions| positions.iter().map(|&pos| &self.rows[pos]).collect())
+            .unwrap_or_default()
+    }
+
+    /// Rows whose key starts with `prefix` (case-sensitive).
+    pub fn keys_with_prefix(&self, prefix: &str) -> Vec<&'a CatalogEntry> {
+        let start = self
+            .sorted_keys
+            .partition_point(|&pos| self.rows[pos].key.as_str() < prefix);
+        let mut positions = self.sorted_keys[start..]
+            .iter()
+            .copied()
+            .take_while(|&pos| self.rows[pos].key.starts_with(prefix))
+            .collect::<Vec<_>>();
+        positions.sort_unstable();
+        positions.into_iter().map(|pos| &self.rows[pos]).collect()
+    }
+
+    /// Rows whose key matches `pattern`.
+    pub fn search_keys(&self, pattern: &Regex) -> Vec<&'a CatalogEntry> {
+        self.rows
+            .iter()
+            .filter(|row| pattern.is_match(&row.key))
+            .collect()
+    }
+
+    /// Rows whose value matches `pattern`.
+    pub fn search_values(&self, pattern: &Regex) -> Vec<&'a CatalogEntry> {
+        self.rows
+            .iter()
+            .filter(|row| pattern.is_match(&row.value))
+            .collect()
+    }
+}
+
+impl StringCatalog {
+    /// Build a [`CatalogIndex`] over the paired rows.
+    pub fn index(&self) -> CatalogIndex<'_> {
+        CatalogIndex::new(&self.rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CatalogIndex;
+    use crate::CatalogEntry;
+    use regex::Regex;
+
+    fn rows() -> Vec<CatalogEntry> {
+        [
+            ("N_10", "Sacred Dominion"),
+            ("D_1", "Primary weapon"),
+            ("N_1", "Shield"),
+            ("N_2", "Helm"),
+            ("N_1", "Duplicate"),
+        ]
+        .into_iter()
+        .enumerate()
+        .map(|(idx, (key, value))| CatalogEntry {
+            index: idx + 1,
+            key: key.to_owned(),
+            value: value.to_owned(),
+        })
+        .collect()
+    }
+
+    fn indices(rows: Vec<&CatalogEntry>) -> Vec<usize> {
+        rows.into_iter().map(|row| row.index).collect()
+    }
+
+    #[test]
+    fn exact_lookup_returns_first_and_all_rows() {
+        let rows = rows();
+        let index = CatalogIndex::new(&rows);
+
+        assert_eq!(
+            index.get("N_1").map(|row| row.value.as_str()),
+            Some("Shield")
+        );
+        assert_eq!(indices(index.get_all("N_1")), [3, 5]);
+        assert!(index.get("n_1").is_none());
+        assert!(index.get_all("missing").is_empty());
+    }
+
+    #[test]
+    fn prefix_search_returns_rows_in_catalog_order() {
+        let rows = rows();
+        let index = CatalogIndex::new(&rows);
+
+        assert_eq!(indices(index.keys_with_prefix("N_1")), [1, 3, 5]);
+        assert_eq!(indices(index.keys_with_prefix("D_")), [2]);
+        assert_eq!(index.keys_with_prefix("").len(), rows.len());
+        assert!(index.keys_with_prefix("Z").is_empty());
+    }
+
+    #[test]
+    fn regex_search_matches_keys_and_values() {
+        let rows = rows();
+        let index = CatalogIndex::new(&rows);
+
+        let keys = Regex::new(r"^N_\d$").expect("regex");
+        assert_eq!(indices(index.search_keys(&keys)), [3, 4, 5]);
+
+        let values = Regex::new("(?i)^s").expect("regex");
+        assert_eq!(indices(index.search_values(&values)), [1, 3]);
+    }
+}
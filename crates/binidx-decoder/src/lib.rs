@@ -1,5 +1,9 @@
 #![forbid(unsafe_code)]
 
+pub use index::CatalogIndex;
+
+mod index;
+
 /// A parsed string entry in a decoded table.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct StringEntry {
@@ -48,6 +52,17 @@ pub struct StringCatalog {
     pub extra_values: Vec<StringEntry>,
 }
 
+/// A scored XOR key from [`detect_xor_key`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct XorCandidate {
+    /// The XOR key.
+    pub key: u8,
+    /// Number of well-formed entries recovered by [`parse_table`].
+    pub entries: usize,
+    /// Number of unparsed trailing bytes.
+    pub trailer_len: usize,
+}
+
 /// Errors produced while decoding or parsing tables.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum DecodeError {
@@ -67,6 +82,43 @@ impl std::fmt::Display for DecodeError {
 
 impl std::error::Error for DecodeError {}
 
+/// Errors produced while encoding tables.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EncodeError {
+    /// Entry was empty; a zero length ends the table when parsed.
+    EmptyEntry { index: usize },
+    /// Entry does not fit in a `u16` length field.
+    EntryTooLong { index: usize, len: usize },
+}
+
+impl std::fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EncodeError::EmptyEntry { index } => {
+                write!(f, "entry {index} is empty and would end the table")
+            }
+            EncodeError::EntryTooLong { index, len } => {
+                write!(
+                    f,
+                    "entry {index} is too long ({len} bytes, max {})",
+                    u16::MAX
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for EncodeError {}
+
+/// Encoded `*.idx` + `*.bin` pair from [`encode_catalog_tables`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncodedTables {
+    /// Encoded index (key) table.
+    pub idx: Vec<u8>,
+    /// Encoded value table.
+    pub bin: Vec<u8>,
+}
+
 /// Apply XOR decoding to a byte slice.
 pub fn xor_decode(input: &[u8], key: u8) -> Vec<u8> {
     input.iter().map(|byte| byte ^ key).collect()
@@ -159,11 +211,109 @@ pub fn decode_catalog_from_encoded_tables(
     decode_catalog_from_decoded_tables(&idx_decoded, &bin_decoded)
 }
 
+/// Build a decoded `[header][u16_le length][utf8 bytes]...` table.
+///
+/// This is the inverse of [`parse_table`]: parsing the output yields the same
+/// header and values with an empty trailer.
+pub fn encode_table<S: AsRef<str>>(header: [u8; 2], values: &[S]) -> Result<Vec<u8>, EncodeError> {
+    let mut encoded = header.to_vec();
+    for (idx, value) in values.iter().enumerate() {
+        push_entry(&mut encoded, idx + 1, value.as_ref())?;
+    }
+    Ok(encoded)
+}
+
+/// Re-encode a parsed table, including its trailer, to decoded bytes.
+///
+/// Tables returned by [`parse_table`] are reproduced byte for byte, so an
+/// edited [`StringTable`] can be written back as a patched table.
+pub fn encode_string_table(table: &StringTable) -> Result<Vec<u8>, EncodeError> {
+    let mut encoded = table.header.to_vec();
+    for (idx, entry) in table.entries.iter().enumerate() {
+        push_entry(&mut encoded, idx + 1, &entry.value)?;
+    }
+    encoded.extend_from_slice(&table.trailer);
+    Ok(encoded)
+}
+
+/// Build XOR-encoded index/value tables from key/value rows.
+///
+/// Both tables share `header`. The output round-trips with
+/// [`decode_catalog_from_encoded_tables`] using the same `xor_key`.
+pub fn encode_catalog_tables<K: AsRef<str>, V: AsRef<str>>(
+    header: [u8; 2],
+    rows: &[(K, V)],
+    xor_key: u8,
+) -> Result<EncodedTables, EncodeError> {
+    let keys = rows.iter().map(|(key, _)| key.as_ref()).collect::<Vec<_>>();
+    let values = rows
+        .iter()
+        .map(|(_, value)| value.as_ref())
+        .collect::<Vec<_>>();
+
+    Ok(EncodedTables {
+        idx: xor_decode(&encode_table(header, &keys)?, xor_key),
+        bin: xor_decode(&encode_table(header, &values)?, xor_key),
+    })
+}
+
+fn push_entry(encoded: &mut Vec<u8>, index: usize, value: &str) -> Result<(), EncodeError> {
+    let raw = value.as_bytes();
+    if raw.is_empty() {
+        return Err(EncodeError::EmptyEntry { index });
+    }
+    let len = u16::try_from(raw.len()).map_err(|_| EncodeError::EntryTooLong {
+        index,
+        len: raw.len(),
+    })?;
+
+    encoded.extend_from_slice(&len.to_le_bytes());
+    encoded.extend_from_slice(raw);
+    Ok(())
+}
+
+/// Try every XOR key against an encoded table and rank the keys that recover entries.
+///
+/// Candidates are ordered by smallest trailer, then most entries, then key.
+pub fn detect_xor_key(encoded: &[u8]) -> Vec<XorCandidate> {
+    detect_xor_key_for_tables(&[encoded])
+}
+
+/// Rank XOR keys by their combined score across tables sharing one key.
+///
+/// Use this with an index/value pair so both tables must decode cleanly.
+pub fn detect_xor_key_for_tables(tables: &[&[u8]]) -> Vec<XorCandidate> {
+    let mut candidates = (0..=u8::MAX)
+        .filter_map(|key| {
+            let mut candidate = XorCandidate {
+                key,
+                entries: 0,
+                trailer_len: 0,
+            };
+            for encoded in tables {
+                let table = parse_table(&xor_decode(encoded, key)).ok()?;
+                candidate.entries += table.entries.len();
+                candidate.trailer_len += table.trailer.len();
+            }
+            (candidate.entries > 0).then_some(candidate)
+        })
+        .collect::<Vec<_>>();
+
+    candidates.sort_by(|a, b| {
+        a.trailer_len
+            .cmp(&b.trailer_len)
+            .then(b.entries.cmp(&a.entries))
+            .then(a.key.cmp(&b.key))
+    });
+    candidates
+}
+
 #[cfg(test)]
 mod tests {
     use super::{
-        DecodeError, decode_catalog_from_decoded_tables, decode_catalog_from_encoded_tables,
-        parse_table, xor_decode,
+        DecodeError, EncodeError, decode_catalog_from_decoded_tables,
+        decode_catalog_from_encoded_tables, detect_xor_key, detect_xor_key_for_tables,
+        encode_catalog_tables, encode_string_table, encode_table, parse_table, xor_decode,
     };
 
     fn table_with_strings(strings: &[&str]) -> Vec<u8> {
@@ -265,4 +415,102 @@ mod tests {
         assert_eq!(catalog.rows[0].key, "N_1");
         assert_eq!(catalog.rows[0].value, "Sword");
     }
+
+    #[test]
+    fn detect_xor_key_ranks_the_encoding_key_first() {
+        let key = 0x32;
+        let decoded = table_with_strings(&["N_1", "N_2", "Sacred Dominion", "盾"]);
+        let encoded = xor_decode(&decoded, key);
+
+        let candidates = detect_xor_key(&encoded);
+        assert_eq!(candidates[0].key, key);
+        assert_eq!(candidates[0].entries, 4);
+        assert_eq!(candidates[0].trailer_len, 0);
+        assert!(candidates.iter().all(|candidate| candidate.entries > 0));
+    }
+
+    #[test]
+    fn detect_xor_key_for_tables_combines_scores() {
+        let key = 0x5a;
+        let idx = xor_decode(&table_with_strings(&["N_1", "N_2"]), key);
+        let bin = xor_decode(&table_with_strings(&["Sword", "Shield"]), key);
+
+        let candidates = detect_xor_key_for_tables(&[&idx, &bin]);
+        assert_eq!(candidates[0].key, key);
+        assert_eq!(candidates[0].entries, 4);
+    }
+
+    #[test]
+    fn detect_xor_key_returns_nothing_for_short_tables() {
+        assert!(detect_xor_key(&[0x01]).is_empty());
+    }
+
+    #[test]
+    fn encode_table_round_trips_with_parse_table() {
+        let encoded =
+            encode_table([0x78, 0x00], &["N_1", "Sacred Dominion", "盾"]).expect("encode");
+        assert_eq!(
+            encoded,
+            table_with_strings(&["N_1", "Sacred Dominion", "盾"])
+        );
+
+        let table = parse_table(&encoded).expect("parse table");
+        assert_eq!(table.header, [0x78, 0x00]);
+        let values = table
+            .entries
+            .iter()
+            .map(|entry| entry.value.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(values, ["N_1", "Sacred Dominion", "盾"]);
+        assert!(table.trailer.is_empty());
+    }
+
+    #[test]
+    fn encode_string_table_reproduces_parsed_bytes() {
+        let mut decoded = table_with_strings(&["N_1", "N_2"]);
+        decoded.extend_from_slice(&[5, 0, b'a']);
+        let mut table = parse_table(&decoded).expect("parse table");
+        assert_eq!(encode_string_table(&table).expect("encode"), decoded);
+
+        table.entries[1].value = "Patched".to_owned();
+        let patched = parse_table(&encode_string_table(&table).expect("encode")).expect("parse");
+        assert_eq!(patched.entries[1].value, "Patched");
+        assert_eq!(patched.trailer, vec![5, 0, b'a']);
+    }
+
+    #[test]
+    fn encode_catalog_tables_round_trips_with_decoder() {
+        let rows = [("N_1", "Sword"), ("N_2", "Shield")];
+        let tables = encode_catalog_tables([0x78, 0x00], &rows, 0x32).expect("encode");
+        assert_eq!(
+            detect_xor_key_for_tables(&[&tables.idx, &tables.bin])[0].key,
+            0x32
+        );
+
+        let catalog =
+            decode_catalog_from_encoded_tables(&tables.idx, &tables.bin, 0x32).expect("decode");
+        let pairs = catalog
+            .rows
+            .iter()
+            .map(|row| (row.key.as_str(), row.value.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(pairs, rows);
+        assert!(catalog.extra_keys.is_empty());
+    }
+
+    #[test]
+    fn encode_table_rejects_entries_parse_table_cannot_read() {
+        assert_eq!(
+            encode_table([0, 0], &["N_1", ""]),
+            Err(EncodeError::EmptyEntry { index: 2 })
+        );
+        let long = "x".repeat(usize::from(u16::MAX) + 1);
+        assert_eq!(
+            encode_table([0, 0], &[long.as_str()]),
+            Err(EncodeError::EntryTooLong {
+                index: 1,
+                len: long.len()
+            })
+        );
+    }
 }
@@ -0,0 +1,374 @@
+#![forbid(unsafe_code)]
+
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use anyhow::Context;
+use binidx_decoder::{decode_catalog_from_encoded_tables, detect_xor_key_for_tables};
+use clap::{Parser, Subcommand};
+use rokbattles_datasets::{
+    Catalogs, DatasetError, ImportOptions, Locale, NameRule, ValidationIssue, import_names_dir,
+    validate_dir, validate_embedded,
+};
+use serde_json::json;
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "datasets-cli",
+    version,
+    about = "Maintain the YAML datasets under datasets/"
+)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Check datasets for duplicate ids, missing locales, empty names and out-of-range ids.
+    ///
+    /// Prints a JSON report and exits with status 1 when any issue is found.
+    Validate {
+        /// Dataset directory to check. Defaults to the datasets embedded at build time.
+        #[arg(long, value_name = "DIR")]
+        dir: Option<PathBuf>,
+
+        /// Emit one JSON issue per line instead of a single report object.
+        #[arg(long)]
+        ndjson: bool,
+    },
+    /// Fill dataset name blocks from decoded game string tables.
+    ///
+    /// Prints a JSON report of added and changed translations.
+    ImportStrings {
+        /// Path to the shared index table (`*.idx`).
+        #[arg(
+            long = "index",
+            visible_alias = "idx",
+            short = 'i',
+            value_name = "PATH"
+        )]
+        index: PathBuf,
+
+        /// Value table for a locale, as `LOCALE=PATH` (for example `en=table_en.bin`).
+        #[arg(
+            long,
+            short = 'b',
+            value_name = "LOCALE=PATH",
+            value_parser = parse_locale_path,
+            required = true
+        )]
+        bin: Vec<(Locale, PathBuf)>,
+
+        /// XOR key as decimal or hex (for example `50` or `0x32`). Detected when omitted.
+        #[arg(long, short = 'x', value_parser = parse_u8, value_name = "KEY")]
+        xor: Option<u8>,
+
+        /// Key pattern rule, as `TARGET=PREFIX{id}SUFFIX[@OFFSET]`
+        /// (for example `equipment.item.name=N_{id}@20000`). Loot targets name
+        /// the loot type, as in `loot.2.name=Gem_{id}`.
+        #[arg(
+            long,
+            short = 'r',
+            value_name = "RULE",
+            value_parser = parse_rule,
+            required = true
+        )]
+        rule: Vec<NameRule>,
+
+        /// Dataset directory to update.
+        #[arg(long, value_name = "DIR", default_value = "datasets")]
+        dir: PathBuf,
+
+        /// Append entries for catalog ids missing from the datasets.
+        #[arg(long)]
+        add_new: bool,
+
+        /// Report changes without writing files.
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+pub fn run(cli: Cli) -> anyhow::Result<ExitCode> {
+    match cli.command {
+        Command::Validate { dir, ndjson } => {
+            let issues = match &dir {
+                Some(dir) => validate_dir(dir)
+                    .with_context(|| format!("failed to read datasets in {}", dir.display()))?,
+                None => validate_embedded(),
+            };
+
+            let mut stdout = io::stdout().lock();
+            if ndjson {
+                write_issues_ndjson(&mut stdout, &issues)?;
+            } else {
+                write_report_json(&mut stdout, &issues)?;
+            }
+
+            Ok(if issues.is_empty() {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            })
+        }
+        Command::ImportStrings {
+            index,
+            bin,
+            xor,
+            rule,
+            dir,
+            add_new,
+            dry_run,
+        } => {
+            let catalogs = read_catalogs(&index, &bin, xor)?;
+            let report =
+                import_names_dir(&dir, &rule, &catalogs, ImportOptions { add_new, dry_run })
+                    .with_context(|| format!("failed to update datasets in {}", dir.display()))?;
+
+            let mut stdout = io::stdout().lock();
+            serde_json::to_writer_pretty(&mut stdout, &report)?;
+            writeln!(stdout)?;
+            Ok(ExitCode::SUCCESS)
+        }
+    }
+}
+
+fn read_catalogs(
+    index: &Path,
+    bins: &[(Locale, PathBuf)],
+    xor: Option<u8>,
+) -> anyhow::Result<Catalogs> {
+    let idx = std::fs::read(index)
+        .with_context(|| format!("failed to read index table {}", index.display()))?;
+
+    let mut catalogs = Catalogs::new();
+    for (locale, bin_path) in bins {
+        let bin = std::fs::read(bin_path)
+            .with_context(|| format!("failed to read value table {}", bin_path.display()))?;
+        let xor = match xor {
+            Some(xor) => xor,
+            None => detect_xor_key_for_tables(&[&idx, &bin])
+                .first()
+                .map(|candidate| candidate.key)
+                .with_context(|| {
+                    format!(
+                        "could not detect the XOR key for {}; pass --xor",
+                        bin_path.display()
+                    )
+                })?,
+        };
+        let catalog = decode_catalog_from_encoded_tables(&idx, &bin, xor).with_context(|| {
+            format!(
+                "failed to decode {} + {}",
+                index.display(),
+                bin_path.display()
+            )
+        })?;
+        let strings = catalogs.entry(*locale).or_default();
+        for row in catalog.rows {
+            strings.insert(row.key, row.value);
+        }
+    }
+    Ok(catalogs)
+}
+
+fn parse_locale_path(input: &str) -> Result<(Locale, PathBuf), String> {
+    let (code, path) = input
+        .split_once('=')
+        .ok_or_else(|| format!("expected LOCALE=PATH, got {input}"))?;
+    let locale = Locale::from_code(code).ok_or_else(|| format!("unsupported locale: {code}"))?;
+    Ok((locale, PathBuf::from(path)))
+}
+
+fn parse_rule(input: &str) -> Result<NameRule, String> {
+    input
+        .parse()
+        .map_err(|error: DatasetError| error.to_string())
+}
+
+fn parse_u8(input: &str) -> Result<u8, String> {
+    if let Some(hex) = input
+        .strip_prefix("0x")
+        .or_else(|| input.strip_prefix("0X"))
+    {
+        return u8::from_str_radix(hex, 16).map_err(|error| error.to_string());
+    }
+
+    input.parse::<u8>().map_err(|error| error.to_string())
+}
+
+fn write_report_json<W: Write>(writer: &mut W, issues: &[ValidationIssue]) -> anyhow::Result<()> {
+    let mut counts = BTreeMap::new();
+    for issue in issues {
+        *counts.entry(issue.kind).or_insert(0usize) += 1;
+    }
+    let report = json!({
+        "valid": issues.is_empty(),
+        "counts": counts,
+        "issues": issues,
+    });
+    serde_json::to_writer_pretty(&mut *writer, &report)?;
+    writeln!(writer)?;
+    Ok(())
+}
+
+fn write_issues_ndjson<W: Write>(writer: &mut W, issues: &[ValidationIssue]) -> anyhow::Result<()> {
+    for issue in issues {
+        serde_json::to_writer(&mut *writer, issue)?;
+        writeln!(writer)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rokbattles_datasets::IssueKind;
+    use serde_json::Value;
+
+    fn issue(kind: IssueKind, path: &str) -> ValidationIssue {
+        ValidationIssue {
+            file: "formations.yaml",
+            kind,
+            path: path.to_owned(),
+            line: None,
+            message: "problem".to_owned(),
+        }
+    }
+
+    #[test]
+    fn cli_parses_validate_subcommand() {
+        let cli = Cli::try_parse_from(["datasets-cli", "validate", "--dir", "datasets"])
+            .expect("parse args");
+        let Command::Validate { dir, ndjson } = cli.command else {
+            panic!("expected validate");
+        };
+        assert_eq!(dir, Some(PathBuf::from("datasets")));
+        assert!(!ndjson);
+    }
+
+    #[test]
+    fn cli_parses_import_strings_subcommand() {
+        let cli = Cli::try_parse_from([
+            "datasets-cli",
+            "import-strings",
+            "--idx",
+            "table.idx",
+            "-b",
+            "en=table_en.bin",
+            "-b",
+            "zh-CN=table_cn.bin",
+            "--xor",
+            "0x32",
+            "--rule",
+            "equipment.item.name=N_{id}@20000",
+            "--dry-run",
+        ])
+        .expect("parse args");
+        let Command::ImportStrings {
+            bin,
+            xor,
+            rule,
+            dir,
+            dry_run,
+            add_new,
+            ..
+        } = cli.command
+        else {
+            panic!("expected import-strings");
+        };
+        assert_eq!(
+            bin,
+            [
+                (Locale::En, PathBuf::from("table_en.bin")),
+                (Locale::ZhCn, PathBuf::from("table_cn.bin")),
+            ]
+        );
+        assert_eq!(xor, Some(0x32));
+        assert_eq!(rule[0].id_for_key("N_7"), Some(20007));
+        assert_eq!(dir, PathBuf::from("datasets"));
+        assert!(dry_run);
+        assert!(!add_new);
+    }
+
+    #[test]
+    fn cli_rejects_invalid_rules_and_locales() {
+        let base = ["datasets-cli", "import-strings", "-i", "t.idx", "-x", "1"];
+        let bad_rule =
+            Cli::try_parse_from(base.into_iter().chain(["-b", "en=a.bin", "-r", "x=N_{id}"]));
+        assert!(bad_rule.is_err());
+        let bad_locale = Cli::try_parse_from(base.into_iter().chain([
+            "-b",
+            "xx=a.bin",
+            "-r",
+            "formations.name=F_{id}",
+        ]));
+        assert!(bad_locale.is_err());
+    }
+
+    #[test]
+    fn read_catalogs_decodes_each_locale() {
+        fn encoded(strings: &[&str]) -> Vec<u8> {
+            let mut table = vec![0, 0];
+            for text in strings {
+                table.extend((text.len() as u16).to_le_bytes());
+                table.extend(text.as_bytes());
+            }
+            table.iter().map(|byte| byte ^ 0x32).collect()
+        }
+
+        let temp = tempfile::tempdir().expect("temp dir");
+        let idx = temp.path().join("table.idx");
+        let en = temp.path().join("table_en.bin");
+        let de = temp.path().join("table_de.bin");
+        std::fs::write(&idx, encoded(&["F_1", "F_2"])).unwrap();
+        std::fs::write(&en, encoded(&["Wedge", "Line"])).unwrap();
+        std::fs::write(&de, encoded(&["Keil", "Linie"])).unwrap();
+
+        let catalogs =
+            read_catalogs(&idx, &[(Locale::En, en), (Locale::De, de)], None).expect("catalogs");
+        assert_eq!(catalogs[&Locale::En]["F_2"], "Line");
+        assert_eq!(catalogs[&Locale::De]["F_1"], "Keil");
+    }
+
+    #[test]
+    fn report_json_counts_issue_kinds() {
+        let issues = [
+            issue(IssueKind::MissingLocale, "formations.1.name.de"),
+            issue(IssueKind::MissingLocale, "formations.2.name.de"),
+            issue(IssueKind::EmptyName, "formations.2.name.fr"),
+        ];
+        let mut output = Vec::new();
+        write_report_json(&mut output, &issues).expect("write report");
+
+        let report: Value = serde_json::from_slice(&output).expect("json");
+        assert_eq!(report["valid"], json!(false));
+        assert_eq!(
+            report["counts"],
+            json!({ "missing_locale": 2, "empty_name": 1 })
+        );
+        assert_eq!(report["issues"][2]["path"], json!("formations.2.name.fr"));
+        assert_eq!(report["issues"][0]["kind"], json!("missing_locale"));
+    }
+
+    #[test]
+    fn ndjson_writes_one_issue_per_line() {
+        let issues = [
+            issue(IssueKind::DuplicateId, "formations.1"),
+            issue(IssueKind::EmptyName, "formations.2.name.fr"),
+        ];
+        let mut output = Vec::new();
+        write_issues_ndjson(&mut output, &issues).expect("write ndjson");
+
+        let text = String::from_utf8(output).expect("utf8");
+        let lines: Vec<Value> = text
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("json line"))
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["kind"], json!("duplicate_id"));
+    }
+}
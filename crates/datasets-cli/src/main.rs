@@ -0,0 +1,11 @@
+#![forbid(unsafe_code)]
+
+use std::process::ExitCode;
+
+use clap::Parser;
+use datasets_cli::{Cli, run};
+
+fn main() -> anyhow::Result<ExitCode> {
+    let cli = Cli::parse();
+    run(cli)
+}
@@ -1,5 +1,7 @@
 use std::path::PathBuf;
 
+use rokbattles_datasets::Locale;
+
 /// Configuration for decoding a directory of mail buffers.
 #[derive(Debug, Clone)]
 pub struct Config {
@@ -11,6 +13,8 @@ pub struct Config {
     pub pretty: bool,
     /// Whether to decode using the lossless representation.
     pub lossless: bool,
+    /// Locale used to attach dataset names to processed output, if any.
+    pub locale: Option<Locale>,
 }
 
 /// Summary of a decode run.
@@ -38,3 +42,12 @@ pub struct RebuildSummary {
     /// Number of lossless JSON files rebuilt into raw buffers.
     pub rebuilt_files: usize,
 }
+
+/// Configuration for checking dataset coverage against a directory of mails.
+#[derive(Debug, Clone)]
+pub struct CoverageConfig {
+    /// Directory containing input mail buffers.
+    pub input_dir: PathBuf,
+    /// Directory with dataset YAML files to check against. Defaults to the embedded datasets.
+    pub datasets_dir: Option<PathBuf>,
+}
@@ -0,0 +1,399 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+
+use rokbattles_datasets::Datasets;
+use serde_json::{Value, json};
+
+use crate::run::{collect_input_files, process_mail};
+use crate::{CoverageConfig, MailCliError};
+
+/// Maximum number of example mail ids kept per missing id.
+const EXAMPLE_LIMIT: usize = 5;
+
+/// Dataset categories checked by a coverage scan.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CoverageKind {
+    /// Commander ids (`commanders.yaml`).
+    Commander,
+    /// Commander skill ids, matched against every commander skill list.
+    Skill,
+    /// Formation ids (`formations.yaml`).
+    Formation,
+    /// Equipment item ids (`equipment.yaml`).
+    Equipment,
+    /// Armament stat and buff ids (`armaments.yaml`).
+    Armament,
+    /// Inscription ids (`inscriptions.yaml`).
+    Inscription,
+    /// NPC loot `type:sub_type` pairs (`loot.yaml`).
+    Loot,
+}
+
+impl CoverageKind {
+    /// Stable label used in reports.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CoverageKind::Commander => "commander",
+            CoverageKind::Skill => "skill",
+            CoverageKind::Formation => "formation",
+            CoverageKind::Equipment => "equipment",
+            CoverageKind::Armament => "armament",
+            CoverageKind::Inscription => "inscription",
+            CoverageKind::Loot => "loot",
+        }
+    }
+}
+
+/// An id referenced by scanned mails but absent from the datasets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissingId {
+    /// The dataset category.
+    pub kind: CoverageKind,
+    /// The missing id (`type:sub_type` for loot).
+    pub id: String,
+    /// Number of references across all scanned mails.
+    pub count: usize,
+    /// Up to five mail ids referencing the id, in scan order.
+    pub examples: Vec<String>,
+}
+
+/// Result of a dataset coverage scan.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CoverageReport {
+    /// Number of mail buffers read.
+    pub scanned_files: usize,
+    /// Number of mails with a dedicated processor that were checked.
+    pub processed_mails: usize,
+    /// Missing ids ordered by kind, then by descending count.
+    pub missing: Vec<MissingId>,
+}
+
+impl CoverageReport {
+    /// Render the report as JSON.
+    pub fn to_json(&self) -> Value {
+        let missing: Vec<Value> = self
+            .missing
+            .iter()
+            .map(|entry| {
+                json!({
+                    "kind": entry.kind.as_str(),
+                    "id": entry.id,
+                    "count": entry.count,
+                    "examples": entry.examples,
+                })
+            })
+            .collect();
+        json!({
+            "scanned_files": self.scanned_files,
+            "processed_mails": self.processed_mails,
+            "missing": missing,
+        })
+    }
+}
+
+/// Scan a directory of mail buffers and report ids missing from the datasets.
+pub fn check_coverage(config: &CoverageConfig) -> Result<CoverageReport, MailCliError> {
+    let metadata = fs::metadata(&config.input_dir).map_err(|source| MailCliError::Io {
+        source,
+        path: config.input_dir.clone(),
+    })?;
+    if !metadata.is_dir() {
+        return Err(MailCliError::InvalidInputDir {
+            path: config.input_dir.clone(),
+        });
+    }
+
+    let datasets = match &config.datasets_dir {
+        Some(dir) => Datasets::from_dir(dir),
+        None => Datasets::embedded(),
+    }
+    .map_err(|source| MailCliError::Dataset { source })?;
+
+    let mut scan = CoverageScan::new(&datasets);
+    for input in collect_input_files(&config.input_dir)? {
+        let buffer = fs::read(&input).map_err(|source| MailCliError::Io {
+            source,
+            path: input.clone(),
+        })?;
+        let value = mail_decoder::decode(&buffer).map_err(|source| MailCliError::Decode {
+            source,
+            path: input.clone(),
+        })?;
+        scan.scanned_files += 1;
+        if let Some((mail_type, processed)) = process_mail(&input, &value)? {
+            scan.processed_mails += 1;
+            let mail_id = mail_id(&processed, &input);
+            scan.mail(mail_type, &processed, &mail_id);
+        }
+    }
+
+    Ok(scan.finish())
+}
+
+fn mail_id(processed: &Value, input: &Path) -> String {
+    processed
+        .pointer("/metadata/mail_id")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .unwrap_or_else(|| {
+            input
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        })
+}
+
+struct CoverageScan<'a> {
+    datasets: &'a Datasets,
+    skills: BTreeSet<u64>,
+    scanned_files: usize,
+    processed_mails: usize,
+    missing: BTreeMap<(CoverageKind, String), (usize, Vec<String>)>,
+}
+
+impl<'a> CoverageScan<'a> {
+    fn new(datasets: &'a Datasets) -> Self {
+        let skills = datasets
+            .commanders()
+            .flat_map(|(_, commander)| &commander.skills)
+            .flat_map(|skill| [Some(skill.id), skill.expert_id])
+            .flatten()
+            .collect();
+        Self {
+            datasets,
+            skills,
+            scanned_files: 0,
+            processed_mails: 0,
+            missing: BTreeMap::new(),
+        }
+    }
+
+    fn mail(&mut self, mail_type: &str, processed: &Value, mail_id: &str) {
+        match mail_type {
+            "Battle" => {
+                let players = processed
+                    .get("sender")
+                    .into_iter()
+                    .chain(array(processed.get("opponents")));
+                for player in players {
+                    self.battle_player(player, mail_id);
+                    for participant in array(player.get("participants")) {
+                        self.battle_player(participant, mail_id);
+                    }
+                    for entry in array(player.pointer("/npc/loot")) {
+                        self.loot(entry, mail_id);
+                    }
+                }
+            }
+            "DuelBattle2" => {
+                let players = ["sender", "opponent"]
+                    .into_iter()
+                    .filter_map(|side| processed.get(side));
+                for player in players {
+                    for key in ["primary_commander", "secondary_commander"] {
+                        if let Some(commander) = player.get(key) {
+                            self.commander(commander, mail_id);
+                        }
+                    }
+                    for buff in array(player.get("buffs")) {
+                        self.check_id(CoverageKind::Armament, buff.get("id"), mail_id);
+                    }
+                }
+            }
+            "BarCanyonKillBoss" => {
+                for participant in array(processed.get("participants")) {
+                    for entry in array(participant.get("loot")) {
+                        self.loot(entry, mail_id);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn battle_player(&mut self, player: &Value, mail_id: &str) {
+        for key in ["primary", "secondary"] {
+            if let Some(commander) = player.get("commanders").and_then(|c| c.get(key)) {
+                self.commander(commander, mail_id);
+            }
+        }
+    }
+
+    fn commander(&mut self, commander: &Value, mail_id: &str) {
+        self.check_id(CoverageKind::Commander, commander.get("id"), mail_id);
+        self.check_id(CoverageKind::Formation, commander.get("formation"), mail_id);
+        for skill in array(commander.get("skills")) {
+            self.check_id(CoverageKind::Skill, skill.get("id"), mail_id);
+        }
+        for slot in array(commander.get("equipment_slots")) {
+            self.check_id(CoverageKind::Equipment, slot.get("id"), mail_id);
+        }
+        for armament in array(commander.get("armaments")) {
+            for inscription in array(armament.get("inscriptions")) {
+                self.check_id(CoverageKind::Inscription, inscription.get("id"), mail_id);
+            }
+            for stat in array(armament.get("stats")) {
+                self.check_id(CoverageKind::Armament, stat.get("id"), mail_id);
+            }
+        }
+    }
+
+    fn loot(&mut self, entry: &Value, mail_id: &str) {
+        let loot_type = entry.get("type").and_then(Value::as_u64);
+        let sub_type = entry.get("sub_type").and_then(Value::as_u64);
+        if let (Some(loot_type), Some(sub_type)) = (loot_type, sub_type)
+            && self.datasets.loot(loot_type, sub_type).is_none()
+        {
+            self.record(
+                CoverageKind::Loot,
+                format!("{loot_type}:{sub_type}"),
+                mail_id,
+            );
+        }
+    }
+
+    /// Record a numeric id when it is absent from the matching dataset.
+    ///
+    /// Null and zero ids mark empty slots and are ignored.
+    fn check_id(&mut self, kind: CoverageKind, id: Option<&Value>, mail_id: &str) {
+        let Some(id) = id.and_then(Value::as_u64).filter(|id| *id != 0) else {
+            return;
+        };
+        let known = match kind {
+            CoverageKind::Commander => self.datasets.commander(id).is_some(),
+            CoverageKind::Skill => self.skills.contains(&id),
+            CoverageKind::Formation => self.datasets.formation(id).is_some(),
+            CoverageKind::Equipment => self.datasets.equipment_item(id).is_some(),
+            CoverageKind::Armament => self.datasets.armament(id).is_some(),
+            CoverageKind::Inscription => self.datasets.inscription(id).is_some(),
+            CoverageKind::Loot => true,
+        };
+        if !known {
+            self.record(kind, id.to_string(), mail_id);
+        }
+    }
+
+    fn record(&mut self, kind: CoverageKind, id: String, mail_id: &str) {
+        let (count, examples) = self.missing.entry((kind, id)).or_default();
+        *count += 1;
+        if examples.len() < EXAMPLE_LIMIT && !examples.iter().any(|example| example == mail_id) {
+            examples.push(mail_id.to_string());
+        }
+    }
+
+    fn finish(self) -> CoverageReport {
+        let mut missing: Vec<MissingId> = self
+            .missing
+            .into_iter()
+            .map(|((kind, id), (count, examples))| MissingId {
+                kind,
+                id,
+                count,
+                examples,
+            })
+            .collect();
+        missing.sort_by(|a, b| a.kind.cmp(&b.kind).then(b.count.cmp(&a.count)));
+        CoverageReport {
+            scanned_files: self.scanned_files,
+            processed_mails: self.processed_mails,
+            missing,
+        }
+    }
+}
+
+fn array(value: Option<&Value>) -> impl Iterator<Item = &Value> {
+    value
+        .and_then(Value::as_array)
+        .into_iter()
+        .flat_map(|items| items.iter())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn datasets() -> Datasets {
+        Datasets::embedded().expect("parse datasets")
+    }
+
+    #[test]
+    fn coverage_scan_records_missing_battle_ids() {
+        let datasets = datasets();
+        let mut scan = CoverageScan::new(&datasets);
+        let processed = json!({
+            "sender": {
+                "commanders": {
+                    "primary": {
+                        "id": 1,
+                        "formation": 99,
+                        "skills": [{ "id": 22, "level": 5 }, { "id": 999999, "level": 1 }],
+                        "equipment_slots": [{ "slot": 1, "id": 20001 }],
+                        "armaments": [{
+                            "inscriptions": [{ "id": 101 }, { "id": 9999 }],
+                            "stats": [{ "id": 2004, "value": 0.07 }]
+                        }]
+                    },
+                    "secondary": { "id": 0, "formation": null, "skills": null }
+                }
+            },
+            "opponents": [{
+                "commanders": { "primary": { "id": 424242 } },
+                "participants": [{ "commanders": { "primary": { "id": 424242 } } }],
+                "npc": { "loot": [{ "type": 1, "sub_type": 9 }, { "type": 7, "sub_type": 7 }] }
+            }]
+        });
+        scan.mail("Battle", &processed, "m1");
+        scan.mail("Battle", &processed, "m2");
+        let report = scan.finish();
+
+        let summary: Vec<(&str, &str, usize)> = report
+            .missing
+            .iter()
+            .map(|entry| (entry.kind.as_str(), entry.id.as_str(), entry.count))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("commander", "424242", 4),
+                ("skill", "999999", 2),
+                ("formation", "99", 2),
+                ("inscription", "9999", 2),
+                ("loot", "7:7", 2),
+            ]
+        );
+        assert_eq!(report.missing[0].examples, ["m1", "m2"]);
+    }
+
+    #[test]
+    fn coverage_scan_checks_duelbattle2_buffs() {
+        let datasets = datasets();
+        let mut scan = CoverageScan::new(&datasets);
+        let processed = json!({
+            "sender": {
+                "primary_commander": { "id": 1, "skills": [] },
+                "buffs": [{ "id": 2004 }, { "id": 31337 }]
+            }
+        });
+        scan.mail("DuelBattle2", &processed, "duel");
+        let report = scan.finish();
+        assert_eq!(report.missing.len(), 1);
+        assert_eq!(report.missing[0].kind, CoverageKind::Armament);
+        assert_eq!(report.missing[0].id, "31337");
+    }
+
+    #[test]
+    fn check_coverage_scans_battle_samples() {
+        let input_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../samples/Battle");
+        let report = check_coverage(&CoverageConfig {
+            input_dir,
+            datasets_dir: None,
+        })
+        .unwrap();
+        assert!(report.scanned_files > 0);
+        assert_eq!(report.processed_mails, report.scanned_files);
+        let json = report.to_json();
+        assert!(json["missing"].is_array());
+    }
+}
@@ -2,6 +2,7 @@ use std::path::PathBuf;
 
 use mail_decoder::{DecodeError, LosslessEncodeError};
 use mail_processor_sdk::ProcessError;
+use rokbattles_datasets::DatasetError;
 
 /// Errors that can occur while decoding a directory.
 #[derive(Debug)]
@@ -70,6 +71,11 @@ pub enum MailCliError {
         /// The offending path.
         path: PathBuf,
     },
+    /// Failed to load the datasets used for enrichment.
+    Dataset {
+        /// The dataset error.
+        source: DatasetError,
+    },
 }
 
 impl std::fmt::Display for MailCliError {
@@ -125,6 +131,9 @@ impl std::fmt::Display for MailCliError {
             MailCliError::MissingFileName { path } => {
                 write!(f, "missing file name for path: {}", path.display())
             }
+            MailCliError::Dataset { source } => {
+                write!(f, "failed to load datasets: {source}")
+            }
         }
     }
 }
@@ -138,6 +147,7 @@ impl std::error::Error for MailCliError {
             MailCliError::Process { source, .. } => Some(source),
             MailCliError::LosslessJson { source, .. } => Some(source),
             MailCliError::LosslessEncode { source, .. } => Some(source),
+            MailCliError::Dataset { source } => Some(source),
             _ => None,
         }
     }
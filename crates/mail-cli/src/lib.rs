@@ -7,12 +7,14 @@
 //! input data (or to a specified output directory).
 
 mod config;
+mod coverage;
 mod error;
 mod fs_utils;
 mod lossless;
 mod run;
 
-pub use config::{Config, RebuildConfig, RebuildSummary, RunSummary};
+pub use config::{Config, CoverageConfig, RebuildConfig, RebuildSummary, RunSummary};
+pub use coverage::{CoverageKind, CoverageReport, MissingId, check_coverage};
 pub use error::MailCliError;
 pub use lossless::rebuild_lossless;
 pub use run::run;
@@ -6,7 +6,8 @@ use std::error::Error;
 use std::path::PathBuf;
 
 use clap::{ArgAction, Parser};
-use mail_cli::{Config, MailCliError, RebuildConfig};
+use mail_cli::{Config, CoverageConfig, MailCliError, RebuildConfig};
+use rokbattles_datasets::Locale;
 
 #[derive(Parser, Debug)]
 #[command(name = "mail-cli", version, about = "Decode mail buffers into JSON")]
@@ -34,6 +35,19 @@ struct Cli {
     /// Mail id override for rebuilding a single lossless JSON document.
     #[arg(long, value_name = "MAIL_ID")]
     mail_id: Option<String>,
+
+    /// Attach localized dataset names to processed output (e.g. `en`, `zh_CN`).
+    #[arg(long, value_name = "LOCALE", value_parser = parse_locale)]
+    locale: Option<Locale>,
+
+    /// Report commander, skill, formation, equipment, armament, inscription and loot ids
+    /// missing from the datasets as JSON on stdout.
+    #[arg(long, default_value_t = false)]
+    coverage: bool,
+
+    /// Directory with dataset YAML files for --coverage. Defaults to the embedded datasets.
+    #[arg(long, value_name = "DATASETS_DIR")]
+    datasets_dir: Option<PathBuf>,
 }
 
 fn main() {
@@ -48,6 +62,26 @@ fn main() {
             report_error(&error);
             std::process::exit(1);
         }
+    } else if cli.coverage {
+        let config = CoverageConfig {
+            input_dir: cli.input_dir,
+            datasets_dir: cli.datasets_dir,
+        };
+        match mail_cli::check_coverage(&config) {
+            Ok(report) => {
+                let report = report.to_json();
+                let json = if cli.pretty {
+                    serde_json::to_string_pretty(&report)
+                } else {
+                    serde_json::to_string(&report)
+                };
+                println!("{}", json.expect("serialize coverage report"));
+            }
+            Err(error) => {
+                report_error(&error);
+                std::process::exit(1);
+            }
+        }
     } else {
         let output_dir = cli
             .output_dir
@@ -59,6 +93,7 @@ fn main() {
             output_dir,
             pretty: cli.pretty,
             lossless: cli.lossless,
+            locale: cli.locale,
         };
 
         if let Err(error) = mail_cli::run(&config) {
@@ -68,6 +103,10 @@ fn main() {
     }
 }
 
+fn parse_locale(value: &str) -> Result<Locale, String> {
+    Locale::from_code(value).ok_or_else(|| format!("unsupported locale: {value}"))
+}
+
 fn report_error(error: &MailCliError) {
     eprintln!("{error}");
     let mut source: Option<&(dyn Error + 'static)> = error.source();
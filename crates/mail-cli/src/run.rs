@@ -2,6 +2,7 @@ use std::fs;
 use std::path::{Path, PathBuf};
 
 use mail_decoder::lossless_to_json;
+use rokbattles_datasets::{Datasets, Locale};
 use serde_json::Value;
 
 use crate::fs_utils::is_json_file;
@@ -25,11 +26,25 @@ pub fn run(config: &Config) -> Result<RunSummary, MailCliError> {
         path: config.output_dir.clone(),
     })?;
 
+    let enrichment = match config.locale {
+        Some(locale) => Some(Enrichment {
+            datasets: Datasets::embedded().map_err(|source| MailCliError::Dataset { source })?,
+            locale,
+        }),
+        None => None,
+    };
+
     let input_files = collect_input_files(&config.input_dir)?;
     let mut decoded_files = 0;
 
     for input in input_files {
-        decode_file(&input, &config.output_dir, config.pretty, config.lossless)?;
+        decode_file(
+            &input,
+            &config.output_dir,
+            config.pretty,
+            config.lossless,
+            enrichment.as_ref(),
+        )?;
         decoded_files += 1;
     }
 
@@ -61,6 +76,7 @@ fn decode_file(
     output_dir: &Path,
     pretty: bool,
     lossless: bool,
+    enrichment: Option<&Enrichment>,
 ) -> Result<(), MailCliError> {
     let buffer = fs::read(input).map_err(|source| MailCliError::Io {
         source,
@@ -82,7 +98,7 @@ fn decode_file(
         path: input.to_path_buf(),
     })?;
     write_json(output_dir, input, &value, pretty)?;
-    write_processed_json(output_dir, input, &value, pretty)?;
+    write_processed_json(output_dir, input, &value, pretty, enrichment)?;
     Ok(())
 }
 
@@ -134,13 +150,64 @@ pub(crate) fn processed_output_path(
     Ok(output_dir.join(format!("{file_name}-processed.json")))
 }
 
+/// Datasets and locale used to attach localized names to processed output.
+pub(crate) struct Enrichment {
+    datasets: Datasets,
+    locale: Locale,
+}
+
 /// Write processed JSON if a known processor exists for the mail type.
 pub(crate) fn write_processed_json(
     output_dir: &Path,
     input_path: &Path,
     value: &Value,
     pretty: bool,
+    enrichment: Option<&Enrichment>,
 ) -> Result<(), MailCliError> {
+    let Some((mail_type, mut processed)) = process_mail(input_path, value)? else {
+        return Ok(());
+    };
+    let output_path = processed_output_path(output_dir, input_path)?;
+    if let Some(enrichment) = enrichment {
+        match mail_type {
+            "Battle" => rokbattles_datasets::enrich_battle(
+                &mut processed,
+                &enrichment.datasets,
+                enrichment.locale,
+            ),
+            "DuelBattle2" => rokbattles_datasets::enrich_duelbattle2(
+                &mut processed,
+                &enrichment.datasets,
+                enrichment.locale,
+            ),
+            _ => {}
+        }
+    }
+
+    let json = if pretty {
+        serde_json::to_string_pretty(&processed)
+    } else {
+        serde_json::to_string(&processed)
+    }
+    .map_err(|source| MailCliError::Json {
+        source,
+        path: output_path.clone(),
+    })?;
+
+    fs::write(&output_path, json).map_err(|source| MailCliError::Io {
+        source,
+        path: output_path,
+    })?;
+    Ok(())
+}
+
+/// Run the dedicated processor for a decoded mail, returning its type and output.
+///
+/// Returns `None` when the mail type has no processor.
+pub(crate) fn process_mail<'a>(
+    input_path: &Path,
+    value: &'a Value,
+) -> Result<Option<(&'a str, Value)>, MailCliError> {
     let processed_input = match value {
         Value::Object(_) => Some(value),
         Value::Array(items) => match items.as_slice() {
@@ -150,13 +217,14 @@ pub(crate) fn write_processed_json(
         _ => None,
     };
     let Some(processed_input) = processed_input else {
-        return Ok(());
+        return Ok(None);
     };
 
     // Only emit processed output for mail types with dedicated processors.
     let mail_type = processed_input.get("type").and_then(|value| value.as_str());
-    let processed = match mail_type {
-        Some("BarCanyonKillBoss") => Some(
+    let (mail_type, processed) = match mail_type {
+        Some(mail_type @ "BarCanyonKillBoss") => (
+            mail_type,
             mail_processor_barcanyonkillboss::process_parallel(processed_input).map_err(
                 |source| MailCliError::Process {
                     source,
@@ -164,7 +232,8 @@ pub(crate) fn write_processed_json(
                 },
             )?,
         ),
-        Some("Battle") => Some(
+        Some(mail_type @ "Battle") => (
+            mail_type,
             mail_processor_battle::process_parallel(processed_input).map_err(|source| {
                 MailCliError::Process {
                     source,
@@ -172,7 +241,8 @@ pub(crate) fn write_processed_json(
                 }
             })?,
         ),
-        Some("DuelBattle2") => Some(
+        Some(mail_type @ "DuelBattle2") => (
+            mail_type,
             mail_processor_duelbattle2::process_parallel(processed_input).map_err(|source| {
                 MailCliError::Process {
                     source,
@@ -180,30 +250,14 @@ pub(crate) fn write_processed_json(
                 }
             })?,
         ),
-        _ => None,
+        _ => return Ok(None),
     };
 
-    let processed = match processed {
-        Some(processed) => processed,
-        None => return Ok(()),
-    };
-
-    let output_path = processed_output_path(output_dir, input_path)?;
-    let json = if pretty {
-        serde_json::to_string_pretty(&processed)
-    } else {
-        serde_json::to_string(&processed)
-    }
-    .map_err(|source| MailCliError::Json {
+    let processed = serde_json::to_value(&processed).map_err(|source| MailCliError::Json {
         source,
-        path: output_path.clone(),
-    })?;
-
-    fs::write(&output_path, json).map_err(|source| MailCliError::Io {
-        source,
-        path: output_path,
+        path: input_path.to_path_buf(),
     })?;
-    Ok(())
+    Ok(Some((mail_type, processed)))
 }
 
 #[cfg(test)]
@@ -262,6 +316,7 @@ mod tests {
             output_dir: output_dir.path().to_path_buf(),
             pretty: true,
             lossless: false,
+            locale: None,
         };
         let summary = run(&config).unwrap();
         assert_eq!(summary.decoded_files, 1);
@@ -286,6 +341,7 @@ mod tests {
             output_dir: output_dir.path().to_path_buf(),
             pretty: true,
             lossless: true,
+            locale: None,
         };
         let summary = run(&config).unwrap();
         assert_eq!(summary.decoded_files, 1);
@@ -307,6 +363,7 @@ mod tests {
             output_dir: temp.path().join("out"),
             pretty: true,
             lossless: false,
+            locale: None,
         };
         let err = run(&config).unwrap_err();
         assert!(matches!(err, MailCliError::InvalidInputDir { .. }));
@@ -318,7 +375,7 @@ mod tests {
         let input = temp.path().join("sample.mail");
         let value = json!({ "type": "Unknown" });
 
-        write_processed_json(temp.path(), &input, &value, true).unwrap();
+        write_processed_json(temp.path(), &input, &value, true, None).unwrap();
         let output = processed_output_path(temp.path(), &input).unwrap();
         assert!(!output.exists());
     }
@@ -332,13 +389,33 @@ mod tests {
         let json = fs::read_to_string(sample_path).expect("read sample");
         let value: Value = serde_json::from_str(&json).expect("parse sample");
 
-        write_processed_json(temp.path(), &input, &value, true).unwrap();
+        write_processed_json(temp.path(), &input, &value, true, None).unwrap();
         let output = processed_output_path(temp.path(), &input).unwrap();
         let output_json = fs::read_to_string(output).expect("read processed");
         let parsed: Value = serde_json::from_str(&output_json).expect("parse processed");
         assert_eq!(parsed["metadata"]["mail_id"], json!("1002579517552941234"));
     }
 
+    #[test]
+    fn write_processed_json_enriches_battle_names() {
+        let temp = tempfile::tempdir().expect("temp dir");
+        let input = temp.path().join("sample.mail");
+        let sample_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../../samples/Battle/Persistent.Mail.1002579517552941234.json");
+        let json = fs::read_to_string(sample_path).expect("read sample");
+        let value: Value = serde_json::from_str(&json).expect("parse sample");
+        let enrichment = Enrichment {
+            datasets: Datasets::embedded().expect("parse datasets"),
+            locale: Locale::En,
+        };
+
+        write_processed_json(temp.path(), &input, &value, true, Some(&enrichment)).unwrap();
+        let output = processed_output_path(temp.path(), &input).unwrap();
+        let output_json = fs::read_to_string(output).expect("read processed");
+        let parsed: Value = serde_json::from_str(&output_json).expect("parse processed");
+        assert!(parsed["sender"]["commanders"]["primary"]["name"].is_string());
+    }
+
     #[test]
     fn write_processed_json_handles_singleton_array() {
         let temp = tempfile::tempdir().expect("temp dir");
@@ -349,7 +426,7 @@ mod tests {
         let value: Value = serde_json::from_str(&json).expect("parse sample");
         assert!(matches!(value, Value::Array(_)));
 
-        write_processed_json(temp.path(), &input, &value, true).unwrap();
+        write_processed_json(temp.path(), &input, &value, true, None).unwrap();
         let output = processed_output_path(temp.path(), &input).unwrap();
         let output_json = fs::read_to_string(output).expect("read processed");
         let parsed: Value = serde_json::from_str(&output_json).expect("parse processed");
@@ -365,7 +442,7 @@ mod tests {
         let json = fs::read_to_string(sample_path).expect("read sample");
         let value: Value = serde_json::from_str(&json).expect("parse sample");
 
-        write_processed_json(temp.path(), &input, &value, true).unwrap();
+        write_processed_json(temp.path(), &input, &value, true, None).unwrap();
         let output = processed_output_path(temp.path(), &input).unwrap();
         let output_json = fs::read_to_string(output).expect("read processed");
         let parsed: Value = serde_json::from_str(&output_json).expect("parse processed");
@@ -383,7 +460,7 @@ mod tests {
         let json = fs::read_to_string(sample_path).expect("read sample");
         let value: Value = serde_json::from_str(&json).expect("parse sample");
 
-        write_processed_json(temp.path(), &input, &value, true).unwrap();
+        write_processed_json(temp.path(), &input, &value, true, None).unwrap();
         let output = processed_output_path(temp.path(), &input).unwrap();
         let output_json = fs::read_to_string(output).expect("read processed");
         let parsed: Value = serde_json::from_str(&output_json).expect("parse processed");
@@ -7,7 +7,7 @@ mod metadata;
 mod npc;
 mod participants;
 
-use mail_processor_sdk::{ProcessError, ProcessedMail, Processor};
+use mail_processor_sdk::{ProcessError, ProcessStats, ProcessedMail, Processor};
 use serde_json::Value;
 
 pub use mail_processor_sdk::{ExtractError, Section};
@@ -17,6 +17,13 @@ pub fn process_parallel(input: &Value) -> Result<ProcessedMail, ProcessError> {
     processor().process_parallel(input)
 }
 
+/// Process a decoded BarCanyonKillBoss mail with parallel extractors and record per-section stats.
+pub fn process_parallel_with_stats(
+    input: &Value,
+) -> (Result<ProcessedMail, ProcessError>, ProcessStats) {
+    processor().process_parallel_with_stats(input)
+}
+
 /// Process a decoded BarCanyonKillBoss mail in extractor order.
 pub fn process_sequential(input: &Value) -> Result<ProcessedMail, ProcessError> {
     processor().process_sequential(input)
@@ -1,6 +1,7 @@
 //! NPC extractor for BarCanyonKillBoss mail.
 
 use mail_processor_sdk::{ExtractError, Extractor, Section};
+use rokbattles_ids::NpcBoss;
 use serde_json::{Map, Value};
 
 use crate::content::{
@@ -25,9 +26,6 @@ impl Extractor for NpcExtractor {
 
     fn extract(&self, input: &Value) -> Result<Section, ExtractError> {
         let content = require_content(input)?;
-        // mappings
-        // - 102000063: Miser Khaolak
-        // - 102000055: Ironhand Baulur
         let npc_type = require_u64_field(content, "npcType")?;
         let npc_level = require_u64_field(content, "npcLevel")?;
         let pos = require_child_object(content, "pos")?;
@@ -38,6 +36,7 @@ impl Extractor for NpcExtractor {
 
         let mut section = Section::new();
         section.insert("type", Value::from(npc_type));
+        section.insert("boss", Value::from(NpcBoss::from_id(npc_type).label()));
         section.insert("level", Value::from(npc_level));
         section.insert("location", location);
         Ok(section)
@@ -75,6 +74,7 @@ mod tests {
 
         let fields = section.fields();
         assert_eq!(fields["type"], json!(102000063));
+        assert_eq!(fields["boss"], json!("miser_khaolak"));
         assert_eq!(fields["level"], json!(32));
         assert_eq!(fields["location"], json!({ "x": 1.5, "y": 2.75 }));
     }
@@ -104,6 +104,7 @@ mod tests {
         let section = extractor.extract(&value).expect("extract sample");
         let fields = section.fields();
         assert_eq!(fields["type"], json!(102000055));
+        assert_eq!(fields["boss"], json!("ironhand_baulur"));
         assert_eq!(fields["level"], json!(25));
         assert_eq!(
             fields["location"],
@@ -0,0 +1,85 @@
+//! Compares opponent extraction strategies over the Battle samples.
+//!
+//! `per_attack` allows one worker per attack, matching the old
+//! thread-per-attack behavior; `bounded` uses the default worker pool and
+//! `sequential` stays on the extractor thread.
+//!
+//! Run with `cargo bench -p mail-processor-battle --bench opponents`.
+
+use std::fs;
+use std::path::PathBuf;
+
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use mail_processor_battle::{Parallelism, processor_with_parallelism};
+use serde_json::Value;
+
+fn load_samples() -> Vec<(String, Value, usize)> {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../samples/Battle");
+    let mut samples = fs::read_dir(&dir)
+        .expect("read samples dir")
+        .filter_map(|entry| {
+            let path = entry.expect("read sample entry").path();
+            let name = path.file_name()?.to_str()?.to_string();
+            if !name.ends_with(".json") || name.ends_with("-processed.json") {
+                return None;
+            }
+            let json = fs::read_to_string(&path).expect("read sample");
+            let value: Value = serde_json::from_str(&json).expect("parse sample");
+            let attacks = value
+                .pointer("/body/content/Attacks")
+                .and_then(Value::as_object)?
+                .len();
+            Some((name, value, attacks))
+        })
+        .collect::<Vec<_>>();
+    samples.sort_by(|a, b| a.0.cmp(&b.0));
+    samples
+}
+
+fn strategies() -> [(&'static str, Parallelism); 3] {
+    [
+        ("per_attack", Parallelism::new(usize::MAX)),
+        ("bounded", Parallelism::default()),
+        ("sequential", Parallelism::sequential()),
+    ]
+}
+
+fn bench_opponents(c: &mut Criterion) {
+    let samples = load_samples();
+    let total_attacks = samples
+        .iter()
+        .map(|(_, _, attacks)| *attacks)
+        .sum::<usize>();
+    let (largest_name, largest, largest_attacks) = samples
+        .iter()
+        .max_by_key(|(_, _, attacks)| *attacks)
+        .expect("at least one Battle sample");
+
+    let mut all = c.benchmark_group("battle_samples");
+    all.sample_size(10);
+    all.throughput(Throughput::Elements(total_attacks as u64));
+    for (name, parallelism) in strategies() {
+        let processor = processor_with_parallelism(parallelism);
+        all.bench_function(BenchmarkId::from_parameter(name), |b| {
+            b.iter(|| {
+                for (_, sample, _) in &samples {
+                    processor.process_parallel(sample).expect("process sample");
+                }
+            });
+        });
+    }
+    all.finish();
+
+    let mut largest_group = c.benchmark_group(format!("largest_sample/{largest_name}"));
+    largest_group.throughput(Throughput::Elements(*largest_attacks as u64));
+    for (name, parallelism) in strategies() {
+        let processor = processor_with_parallelism(parallelism);
+        largest_group.bench_function(BenchmarkId::from_parameter(name), |b| {
+            b.iter(|| processor.process_parallel(largest).expect("process sample"));
+        });
+    }
+    largest_group.finish();
+}
+
+criterion_group!(benches, bench_opponents);
+criterion_main!(benches);
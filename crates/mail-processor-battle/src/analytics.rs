@@ -0,0 +1,362 @@
+//! Derived combat analytics for Battle mail.
+
+use mail_processor_sdk::{ExtractError, Extractor, Section};
+use serde_json::{Map, Value, json};
+
+use crate::content::require_content;
+use crate::opponents::{parse_attack_id, require_attacks, sort_by_attack_order};
+
+/// Derives ratios and deltas from each attack's battle results.
+///
+/// Losses are dead plus severely wounded troops, the units a side cannot
+/// field again after the battle. Ratios with a zero denominator are null.
+#[derive(Debug, Default)]
+pub struct AnalyticsExtractor;
+
+impl AnalyticsExtractor {
+    /// Create a new analytics extractor.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Extractor for AnalyticsExtractor {
+    fn section(&self) -> &'static str {
+        "analytics"
+    }
+
+    fn extract(&self, input: &Value) -> Result<Section, ExtractError> {
+        let content = require_content(input)?;
+        let attacks = require_attacks(content)?;
+
+        let mut entries = Vec::with_capacity(attacks.len());
+        for (attack_key, attack) in attacks {
+            let attack = attack.as_object().ok_or(ExtractError::InvalidFieldType {
+                field: "Attacks",
+                expected: "object",
+            })?;
+            entries.push((parse_attack_id(attack_key)?, attack_key, attack));
+        }
+        sort_by_attack_order(&mut entries);
+
+        let mut total = AttackTotals::default();
+        let mut per_attack = Vec::with_capacity(entries.len());
+        for (_, attack_key, attack) in entries {
+            let sender = SideCounters::read(attack.get("Damage"));
+            let opponent = SideCounters::read(attack.get("Kill"));
+            let ticks = tick_bounds(attack);
+            total.add(sender, opponent, ticks);
+
+            let mut analytics = combat_analytics(sender, opponent, duration(ticks));
+            analytics.insert("attack_id".to_string(), Value::from(attack_key.as_str()));
+            per_attack.push(Value::Object(analytics));
+        }
+
+        let total_duration = total
+            .start_tick
+            .zip(total.end_tick)
+            .map(|(start, end)| end.saturating_sub(start));
+
+        let mut section = Section::new();
+        section.insert("attacks".to_string(), Value::Array(per_attack));
+        section.insert(
+            "total".to_string(),
+            Value::Object(combat_analytics(
+                total.sender,
+                total.opponent,
+                total_duration,
+            )),
+        );
+        Ok(section)
+    }
+}
+
+/// Battle result counters read from a `Damage` or `Kill` payload.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct SideCounters {
+    kill_points: u64,
+    dead: u64,
+    severely_wounded: u64,
+    slightly_wounded: u64,
+    heal: u64,
+    power: i64,
+    attack_power: i64,
+    skill_power: i64,
+}
+
+impl SideCounters {
+    /// Read counters leniently; the opponents section reports malformed values.
+    fn read(value: Option<&Value>) -> Option<Self> {
+        let overview = value?.as_object()?;
+        let unsigned = |field| overview.get(field).and_then(Value::as_u64).unwrap_or(0);
+        let signed = |field| overview.get(field).and_then(Value::as_i64).unwrap_or(0);
+        Some(Self {
+            kill_points: unsigned("KillScore"),
+            dead: unsigned("Death"),
+            severely_wounded: unsigned("BadHurt"),
+            slightly_wounded: unsigned("Hurt"),
+            heal: unsigned("Healing"),
+            power: signed("Power"),
+            attack_power: signed("AtkPower"),
+            skill_power: signed("SkillPower"),
+        })
+    }
+
+    fn add(&mut self, other: &Self) {
+        self.kill_points += other.kill_points;
+        self.dead += other.dead;
+        self.severely_wounded += other.severely_wounded;
+        self.slightly_wounded += other.slightly_wounded;
+        self.heal += other.heal;
+        self.power += other.power;
+        self.attack_power += other.attack_power;
+        self.skill_power += other.skill_power;
+    }
+
+    fn losses(&self) -> u64 {
+        self.dead + self.severely_wounded
+    }
+}
+
+/// Counters summed across every attack in the report.
+#[derive(Debug, Default)]
+struct AttackTotals {
+    sender: Option<SideCounters>,
+    opponent: Option<SideCounters>,
+    start_tick: Option<u64>,
+    end_tick: Option<u64>,
+}
+
+impl AttackTotals {
+    fn add(
+        &mut self,
+        sender: Option<SideCounters>,
+        opponent: Option<SideCounters>,
+        (start_tick, end_tick): (Option<u64>, Option<u64>),
+    ) {
+        for (total, side) in [(&mut self.sender, sender), (&mut self.opponent, opponent)] {
+            if let Some(side) = side {
+                total.get_or_insert_with(SideCounters::default).add(&side);
+            }
+        }
+        self.start_tick = min_option(self.start_tick, start_tick);
+        self.end_tick = self.end_tick.max(end_tick);
+    }
+}
+
+fn min_option(a: Option<u64>, b: Option<u64>) -> Option<u64> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+/// Read the attack's `Bts`/`Ets` boundary ticks.
+fn tick_bounds(attack: &Map<String, Value>) -> (Option<u64>, Option<u64>) {
+    (
+        attack.get("Bts").and_then(Value::as_u64),
+        attack.get("Ets").and_then(Value::as_u64),
+    )
+}
+
+fn duration((start_tick, end_tick): (Option<u64>, Option<u64>)) -> Option<u64> {
+    start_tick
+        .zip(end_tick)
+        .map(|(start, end)| end.saturating_sub(start))
+}
+
+/// Build the analytics object for one attack or the whole report.
+fn combat_analytics(
+    sender: Option<SideCounters>,
+    opponent: Option<SideCounters>,
+    duration_ticks: Option<u64>,
+) -> Map<String, Value> {
+    // Power values are negative losses, so a positive swing favours the sender.
+    let power_swing = match (sender, opponent) {
+        (Some(sender), Some(opponent)) => json!({
+            "power": sender.power - opponent.power,
+            "attack_power": sender.attack_power - opponent.attack_power,
+            "skill_power": sender.skill_power - opponent.skill_power,
+        }),
+        _ => Value::Null,
+    };
+
+    let mut analytics = Map::new();
+    analytics.insert(
+        "duration_ticks".to_string(),
+        duration_ticks.map(Value::from).unwrap_or(Value::Null),
+    );
+    analytics.insert("sender".to_string(), side_analytics(sender, opponent));
+    analytics.insert("opponent".to_string(), side_analytics(opponent, sender));
+    analytics.insert("power_swing".to_string(), power_swing);
+    analytics
+}
+
+/// Derive one side's ratios against the other side's losses.
+fn side_analytics(own: Option<SideCounters>, other: Option<SideCounters>) -> Value {
+    let Some(own) = own else {
+        return json!({
+            "kill_points": Value::Null,
+            "losses": Value::Null,
+            "kill_loss_ratio": Value::Null,
+            "troops_lost_per_kill_point": Value::Null,
+            "heal_ratio": Value::Null,
+            "wound_to_death_conversion": Value::Null,
+        });
+    };
+    let kill_loss_ratio = match other {
+        Some(other) => ratio(other.losses(), own.losses()),
+        None => Value::Null,
+    };
+
+    json!({
+        "kill_points": own.kill_points,
+        "losses": own.losses(),
+        "kill_loss_ratio": kill_loss_ratio,
+        "troops_lost_per_kill_point": ratio(own.losses(), own.kill_points),
+        "heal_ratio": ratio(own.heal, own.severely_wounded + own.slightly_wounded),
+        "wound_to_death_conversion": ratio(own.dead, own.losses()),
+    })
+}
+
+fn ratio(numerator: u64, denominator: u64) -> Value {
+    if denominator == 0 {
+        return Value::Null;
+    }
+    Value::from(numerator as f64 / denominator as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mail_processor_sdk::Extractor;
+    use serde_json::{Value, json};
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn result(
+        kill_points: u64,
+        dead: u64,
+        severe: u64,
+        slight: u64,
+        heal: u64,
+        power: i64,
+    ) -> Value {
+        json!({
+            "KillScore": kill_points,
+            "Death": dead,
+            "BadHurt": severe,
+            "Hurt": slight,
+            "Healing": heal,
+            "Power": power,
+            "AtkPower": power,
+            "SkillPower": 0
+        })
+    }
+
+    #[test]
+    fn analytics_extractor_derives_per_attack_and_total_metrics() {
+        let input = json!({
+            "body": {
+                "content": {
+                    "Attacks": {
+                        "20": {
+                            "Bts": 150,
+                            "Ets": 180,
+                            "Damage": result(100, 0, 50, 50, 25, -400),
+                            "Kill": result(40, 100, 100, 0, 0, -1000)
+                        },
+                        "10": {
+                            "Bts": 100,
+                            "Ets": 130,
+                            "Damage": result(200, 10, 40, 0, 0, -600),
+                            "Kill": result(0, 0, 0, 0, 0, 0)
+                        }
+                    }
+                }
+            }
+        });
+        let section = AnalyticsExtractor::new()
+            .extract(&input)
+            .expect("analytics");
+        let fields = section.fields();
+
+        let attacks = fields["attacks"].as_array().expect("attacks");
+        assert_eq!(attacks[0]["attack_id"], json!("10"));
+        assert_eq!(attacks[0]["duration_ticks"], json!(30));
+        assert_eq!(attacks[0]["sender"]["kill_loss_ratio"], json!(0.0));
+        assert_eq!(attacks[0]["opponent"]["kill_loss_ratio"], Value::Null);
+        assert_eq!(
+            attacks[1]["sender"],
+            json!({
+                "kill_points": 100,
+                "losses": 50,
+                "kill_loss_ratio": 4.0,
+                "troops_lost_per_kill_point": 0.5,
+                "heal_ratio": 0.25,
+                "wound_to_death_conversion": 0.0
+            })
+        );
+        assert_eq!(
+            attacks[1]["opponent"]["wound_to_death_conversion"],
+            json!(0.5)
+        );
+        assert_eq!(
+            attacks[1]["power_swing"],
+            json!({ "power": 600, "attack_power": 600, "skill_power": 0 })
+        );
+
+        let total = &fields["total"];
+        assert_eq!(total["duration_ticks"], json!(80));
+        assert_eq!(total["sender"]["kill_points"], json!(300));
+        assert_eq!(total["sender"]["losses"], json!(100));
+        assert_eq!(total["sender"]["kill_loss_ratio"], json!(2.0));
+        assert_eq!(total["opponent"]["troops_lost_per_kill_point"], json!(5.0));
+        assert_eq!(total["power_swing"]["power"], json!(0));
+    }
+
+    #[test]
+    fn analytics_extractor_nulls_missing_results() {
+        let input = json!({
+            "body": {
+                "content": {
+                    "Attacks": {
+                        "1": { "Damage": result(0, 0, 0, 0, 0, 0) }
+                    }
+                }
+            }
+        });
+        let section = AnalyticsExtractor::new()
+            .extract(&input)
+            .expect("analytics");
+        let attack = &section.fields()["attacks"][0];
+
+        assert_eq!(attack["duration_ticks"], Value::Null);
+        assert_eq!(attack["power_swing"], Value::Null);
+        assert_eq!(attack["opponent"]["losses"], Value::Null);
+        assert_eq!(attack["sender"]["kill_loss_ratio"], Value::Null);
+        assert_eq!(attack["sender"]["heal_ratio"], Value::Null);
+    }
+
+    #[test]
+    fn roundtrip_analytics_extracts_sample() {
+        let sample_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../../samples/Battle/Persistent.Mail.1409019176893142331.json");
+        let json = fs::read_to_string(sample_path).expect("read sample");
+        let value: Value = serde_json::from_str(&json).expect("parse sample");
+        let section = AnalyticsExtractor::new()
+            .extract(&value)
+            .expect("extract sample");
+        let fields = section.fields();
+
+        let attack = &fields["attacks"][0];
+        assert_eq!(attack["attack_id"], json!("10852801"));
+        assert_eq!(attack["duration_ticks"], json!(10));
+        assert_eq!(attack["sender"]["losses"], json!(10));
+        assert_eq!(attack["sender"]["kill_loss_ratio"], json!(2024.7));
+        assert_eq!(attack["sender"]["heal_ratio"], json!(0.0));
+        assert_eq!(attack["opponent"]["wound_to_death_conversion"], json!(1.0));
+        assert_eq!(attack["power_swing"]["power"], json!(80888));
+        assert_eq!(fields["total"]["sender"], attack["sender"]);
+    }
+}
@@ -0,0 +1,196 @@
+//! Armament and inscription decoding for Battle commanders.
+
+use mail_processor_sdk::ExtractError;
+use serde_json::{Map, Value, json};
+
+use crate::content::require_string_field;
+
+// Armament slot (HWBs key) mappings:
+// - 1: scroll
+// - 2: instrument
+// - 3: flag
+// - 4: emblem
+//
+// Inscription ids (Affix) are semicolon separated, with -1 for an empty slot.
+// The hundreds digit is the armament category (1XX scroll .. 4XX emblem).
+// Ids of four digits or more are rare/special inscriptions; the last digit
+// is 1 for special and 2 for rare. Three-digit ids are common inscriptions.
+
+/// Extract typed armament slots from an `HWBs` object.
+pub(crate) fn optional_armaments_field(
+    player: &Map<String, Value>,
+    field: &'static str,
+) -> Result<Value, ExtractError> {
+    let value = match player.get(field) {
+        None | Some(Value::Null) => return Ok(Value::Null),
+        Some(value) => value,
+    };
+    let map = value.as_object().ok_or(ExtractError::InvalidFieldType {
+        field,
+        expected: "object",
+    })?;
+
+    let mut entries = Vec::with_capacity(map.len());
+    for (key, value) in map {
+        let id = key
+            .parse::<u64>()
+            .map_err(|_| ExtractError::InvalidFieldType {
+                field,
+                expected: "numeric object key",
+            })?;
+        let value = value.as_object().ok_or(ExtractError::InvalidFieldType {
+            field,
+            expected: "object",
+        })?;
+        let affix = require_string_field(value, "Affix")?;
+        let buffs = require_string_field(value, "Buffs")?;
+        let inscriptions = parse_inscriptions(&affix);
+        let stats = parse_stats(&buffs);
+        entries.push((
+            id,
+            json!({
+                "id": id,
+                "kind": armament_kind(id),
+                "affix": affix,
+                "buffs": buffs,
+                "inscriptions": inscriptions,
+                "stats": stats,
+            }),
+        ));
+    }
+
+    entries.sort_by_key(|(id, _)| *id);
+    Ok(Value::Array(
+        entries.into_iter().map(|(_, entry)| entry).collect(),
+    ))
+}
+
+/// Parse an `Affix` string into classified inscription entries.
+///
+/// Returns null when any id is malformed instead of guessing.
+fn parse_inscriptions(affix: &str) -> Value {
+    let mut inscriptions = Vec::new();
+    for token in affix
+        .split(';')
+        .map(str::trim)
+        .filter(|token| !token.is_empty())
+    {
+        let Ok(id) = token.parse::<i64>() else {
+            return Value::Null;
+        };
+        // -1 marks an armament slot without inscriptions.
+        let Ok(id) = u64::try_from(id) else {
+            continue;
+        };
+        if id == 0 {
+            continue;
+        }
+        inscriptions.push(json!({
+            "id": id,
+            "category": armament_kind((id / 100) % 10),
+            "rarity": inscription_rarity(id),
+        }));
+    }
+    Value::Array(inscriptions)
+}
+
+/// Parse a `Buffs` string of `id_value` pairs into stat entries.
+///
+/// Returns null when any pair is malformed instead of guessing.
+fn parse_stats(buffs: &str) -> Value {
+    let mut stats = Vec::new();
+    for token in buffs
+        .split(';')
+        .map(str::trim)
+        .filter(|token| !token.is_empty())
+    {
+        let Some((id, value)) = token.split_once('_') else {
+            return Value::Null;
+        };
+        let (Ok(id), Ok(value)) = (id.parse::<u64>(), value.parse::<f64>()) else {
+            return Value::Null;
+        };
+        stats.push(json!({ "id": id, "value": value }));
+    }
+    Value::Array(stats)
+}
+
+fn armament_kind(code: u64) -> &'static str {
+    match code {
+        1 => "scroll",
+        2 => "instrument",
+        3 => "flag",
+        4 => "emblem",
+        _ => "unknown",
+    }
+}
+
+fn inscription_rarity(id: u64) -> &'static str {
+    if id < 1000 {
+        return "common";
+    }
+    match id % 10 {
+        1 => "special",
+        2 => "rare",
+        _ => "unknown",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn optional_armaments_field_classifies_slots() {
+        let player = json!({
+            "HWBs": {
+                "3": { "Affix": "2302;309", "Buffs": "6002_0.028000;8000_0.040000", "Id": "x" },
+                "1": { "Affix": "-1", "Buffs": "2004_0.070000", "Id": "y" }
+            }
+        });
+        let armaments =
+            optional_armaments_field(player.as_object().expect("player"), "HWBs").unwrap();
+        assert_eq!(
+            armaments,
+            json!([
+                {
+                    "id": 1,
+                    "kind": "scroll",
+                    "affix": "-1",
+                    "buffs": "2004_0.070000",
+                    "inscriptions": [],
+                    "stats": [{ "id": 2004, "value": 0.07 }]
+                },
+                {
+                    "id": 3,
+                    "kind": "flag",
+                    "affix": "2302;309",
+                    "buffs": "6002_0.028000;8000_0.040000",
+                    "inscriptions": [
+                        { "id": 2302, "category": "flag", "rarity": "rare" },
+                        { "id": 309, "category": "flag", "rarity": "common" }
+                    ],
+                    "stats": [
+                        { "id": 6002, "value": 0.028 },
+                        { "id": 8000, "value": 0.04 }
+                    ]
+                }
+            ])
+        );
+    }
+
+    #[test]
+    fn parse_inscriptions_reads_special_emblem() {
+        assert_eq!(
+            parse_inscriptions("17401"),
+            json!([{ "id": 17401, "category": "emblem", "rarity": "special" }])
+        );
+    }
+
+    #[test]
+    fn parse_inscriptions_rejects_malformed_ids() {
+        assert_eq!(parse_inscriptions("abc"), Value::Null);
+        assert_eq!(parse_stats("2004"), Value::Null);
+        assert_eq!(parse_stats(""), json!([]));
+    }
+}
@@ -0,0 +1,315 @@
+//! Battle kind classification for Battle mail.
+
+use std::collections::BTreeMap;
+
+use mail_processor_sdk::{ExtractError, Extractor, Section};
+use rokbattles_ids::{AllianceBuilding, Structure};
+use serde_json::{Map, Value, json};
+
+use crate::content::{require_child_object, require_content, require_string_field};
+use crate::opponents::{parse_attack_id, require_attacks, sort_by_attack_order};
+
+/// Kind of fight an attack or report describes.
+///
+/// Variants are ordered from most to least specific; classification picks
+/// the first one whose signals are present.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum BattleKind {
+    Dungeon,
+    Npc,
+    ArkObjective,
+    KvkObjective,
+    AllianceBuilding,
+    GarrisonDefense,
+    Rally,
+    OpenField,
+}
+
+impl BattleKind {
+    /// Stable label used in the output.
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::Dungeon => "dungeon",
+            Self::Npc => "npc",
+            Self::ArkObjective => "ark_objective",
+            Self::KvkObjective => "kvk_objective",
+            Self::AllianceBuilding => "alliance_building",
+            Self::GarrisonDefense => "garrison_defense",
+            Self::Rally => "rally",
+            Self::OpenField => "open_field",
+        }
+    }
+}
+
+/// Labels the report and each attack with a [`BattleKind`].
+///
+/// The report kind is the most common attack kind, with ties going to the
+/// more specific kind. Reports without attacks are classified from the
+/// sender alone.
+#[derive(Debug, Default)]
+pub struct ClassificationExtractor;
+
+impl ClassificationExtractor {
+    /// Create a new classification extractor.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Extractor for ClassificationExtractor {
+    fn section(&self) -> &'static str {
+        "classification"
+    }
+
+    fn extract(&self, input: &Value) -> Result<Section, ExtractError> {
+        let content = require_content(input)?;
+        let dungeon = require_string_field(content, "Role")? == "dungeon";
+        let sender = SideSignals::read(require_child_object(content, "SelfChar")?);
+        let attacks = require_attacks(content)?;
+
+        let mut entries = Vec::with_capacity(attacks.len());
+        for (attack_key, attack) in attacks {
+            let opponent = attack
+                .get("CIdt")
+                .and_then(Value::as_object)
+                .map(SideSignals::read)
+                .unwrap_or_default();
+            let kind = classify(dungeon, sender, opponent);
+            entries.push((parse_attack_id(attack_key)?, attack_key, kind));
+        }
+        sort_by_attack_order(&mut entries);
+
+        let mut counts = BTreeMap::<BattleKind, u64>::new();
+        for (_, _, kind) in &entries {
+            *counts.entry(*kind).or_default() += 1;
+        }
+        let kind = counts
+            .iter()
+            .max_by(|(kind_a, count_a), (kind_b, count_b)| {
+                count_a.cmp(count_b).then_with(|| kind_b.cmp(kind_a))
+            })
+            .map(|(kind, _)| *kind)
+            .unwrap_or_else(|| classify(dungeon, sender, SideSignals::default()));
+
+        let per_attack = entries
+            .into_iter()
+            .map(|(_, attack_key, kind)| json!({ "attack_id": attack_key, "kind": kind.as_str() }))
+            .collect();
+        let counts = counts
+            .into_iter()
+            .map(|(kind, count)| (kind.as_str().to_string(), Value::from(count)))
+            .collect::<Map<_, _>>();
+
+        let mut section = Section::new();
+        section.insert("kind", Value::from(kind.as_str()));
+        section.insert("counts", Value::Object(counts));
+        section.insert("attacks", Value::Array(per_attack));
+        Ok(section)
+    }
+}
+
+/// Classification signals read from one side's character object.
+///
+/// Reads are lenient; the sender and opponents sections report malformed values.
+#[derive(Debug, Default, Clone, Copy)]
+struct SideSignals {
+    rally: bool,
+    structure: Option<Structure>,
+    alliance_building: Option<AllianceBuilding>,
+    npc_type: Option<u64>,
+}
+
+impl SideSignals {
+    fn read(player: &Map<String, Value>) -> Self {
+        Self {
+            rally: player
+                .get("IsRally")
+                .and_then(Value::as_bool)
+                .unwrap_or(false),
+            structure: player
+                .get("ShId")
+                .and_then(Value::as_u64)
+                .map(Structure::from_id),
+            alliance_building: player
+                .get("AbT")
+                .and_then(Value::as_u64)
+                .map(AllianceBuilding::from_id),
+            npc_type: player.get("NpcType").and_then(Value::as_u64),
+        }
+    }
+}
+
+/// Classify one attack from the report role and both sides' signals.
+///
+/// A rallied opponent facing a sender who is not rallying means the sender
+/// defends a garrison; structures were already matched at that point.
+fn classify(dungeon: bool, sender: SideSignals, opponent: SideSignals) -> BattleKind {
+    let either = |check: fn(&SideSignals) -> bool| check(&sender) || check(&opponent);
+
+    if dungeon {
+        BattleKind::Dungeon
+    } else if opponent.npc_type.is_some() {
+        BattleKind::Npc
+    } else if either(|side| side.structure.is_some_and(Structure::is_ark)) {
+        BattleKind::ArkObjective
+    } else if either(|side| side.structure.is_some_and(Structure::is_kvk)) {
+        BattleKind::KvkObjective
+    } else if either(|side| {
+        side.alliance_building
+            .is_some_and(|building| !matches!(building, AllianceBuilding::Unknown(_)))
+    }) {
+        BattleKind::AllianceBuilding
+    } else if opponent.rally && !sender.rally {
+        BattleKind::GarrisonDefense
+    } else if sender.rally {
+        BattleKind::Rally
+    } else {
+        BattleKind::OpenField
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn classify_sample(name: &str) -> BTreeMap<String, Value> {
+        let sample_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../../samples/Battle")
+            .join(name);
+        let json = fs::read_to_string(sample_path).expect("read sample");
+        let value: Value = serde_json::from_str(&json).expect("parse sample");
+        let section = ClassificationExtractor::new()
+            .extract(&value)
+            .expect("extract sample");
+        section.fields().clone()
+    }
+
+    #[test]
+    fn classify_prefers_specific_kinds() {
+        let side = SideSignals::default();
+        let rally = SideSignals {
+            rally: true,
+            ..side
+        };
+        let ark = SideSignals {
+            structure: Some(Structure::SkyAltar),
+            ..side
+        };
+        let pass = SideSignals {
+            structure: Some(Structure::Level7Pass),
+            ..rally
+        };
+        let flag = SideSignals {
+            alliance_building: Some(AllianceBuilding::Flag),
+            ..side
+        };
+        let npc = SideSignals {
+            npc_type: Some(38),
+            ..flag
+        };
+
+        assert_eq!(classify(true, rally, npc), BattleKind::Dungeon);
+        assert_eq!(classify(false, rally, npc), BattleKind::Npc);
+        assert_eq!(classify(false, ark, pass), BattleKind::ArkObjective);
+        assert_eq!(classify(false, side, pass), BattleKind::KvkObjective);
+        assert_eq!(classify(false, rally, flag), BattleKind::AllianceBuilding);
+        assert_eq!(classify(false, side, rally), BattleKind::GarrisonDefense);
+        assert_eq!(classify(false, rally, rally), BattleKind::Rally);
+        assert_eq!(classify(false, rally, side), BattleKind::Rally);
+        assert_eq!(classify(false, side, side), BattleKind::OpenField);
+    }
+
+    #[test]
+    fn classification_extractor_labels_attacks_and_report() {
+        let input = json!({
+            "body": {
+                "content": {
+                    "Role": "gsmp",
+                    "SelfChar": { "IsRally": true },
+                    "Attacks": {
+                        "20": { "CIdt": { "AbT": 1 } },
+                        "3": { "CIdt": {} },
+                        "11": { "CIdt": { "AbT": 3 } }
+                    }
+                }
+            }
+        });
+
+        let section = ClassificationExtractor::new()
+            .extract(&input)
+            .expect("extract classification");
+        let fields = section.fields();
+
+        assert_eq!(fields["kind"], json!("alliance_building"));
+        assert_eq!(
+            fields["counts"],
+            json!({ "alliance_building": 2, "rally": 1 })
+        );
+        assert_eq!(
+            fields["attacks"],
+            json!([
+                { "attack_id": "3", "kind": "rally" },
+                { "attack_id": "11", "kind": "alliance_building" },
+                { "attack_id": "20", "kind": "alliance_building" }
+            ])
+        );
+    }
+
+    #[test]
+    fn classification_extractor_breaks_ties_toward_specific_kinds() {
+        let input = json!({
+            "body": {
+                "content": {
+                    "Role": "gsmp",
+                    "SelfChar": {},
+                    "Attacks": {
+                        "1": { "CIdt": { "IsRally": true } },
+                        "2": { "CIdt": {} }
+                    }
+                }
+            }
+        });
+
+        let section = ClassificationExtractor::new()
+            .extract(&input)
+            .expect("extract classification");
+        assert_eq!(section.fields()["kind"], json!("garrison_defense"));
+    }
+
+    #[test]
+    fn classification_extractor_uses_sender_without_attacks() {
+        let input = json!({
+            "body": {
+                "content": {
+                    "Role": "gsmp",
+                    "SelfChar": { "ShId": 38 },
+                    "Attacks": {}
+                }
+            }
+        });
+
+        let section = ClassificationExtractor::new()
+            .extract(&input)
+            .expect("extract classification");
+        let fields = section.fields();
+        assert_eq!(fields["kind"], json!("kvk_objective"));
+        assert_eq!(fields["counts"], json!({}));
+        assert_eq!(fields["attacks"], json!([]));
+    }
+
+    #[test]
+    fn roundtrip_classification_extracts_samples() {
+        let npc = classify_sample("Persistent.Mail.1409019176893142331.json");
+        assert_eq!(npc["kind"], json!("npc"));
+
+        let dungeon = classify_sample("Persistent.Mail.16210617176935008431.json");
+        assert_eq!(dungeon["kind"], json!("dungeon"));
+
+        let flag = classify_sample("Persistent.Mail.10121648172261838131.json");
+        assert_eq!(flag["counts"]["alliance_building"], json!(2));
+        assert_eq!(flag["kind"], json!("rally"));
+    }
+}
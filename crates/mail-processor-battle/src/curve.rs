@@ -0,0 +1,446 @@
+//! Troop-count curve reconstruction for Battle mail.
+
+use mail_processor_sdk::{ExtractError, Extractor, Section, indexed_array_values, require_u64};
+use serde_json::{Map, Value, json};
+
+use crate::content::{require_content, require_u64_field};
+use crate::opponents::{parse_attack_id, require_attacks, sort_by_attack_order};
+use crate::timeline::{TimelineEvent, parse_events};
+
+/// Merges samples, reinforcement events and attack bounds into one series.
+///
+/// Ticks are seconds, so wall-clock time is `Bts + (tick - Btk)`. Samples
+/// set the absolute troop count; reinforcement events adjust it between
+/// samples. Attack start and end ticks become points of their own so each
+/// opponent segment begins and ends on the curve.
+#[derive(Debug, Default)]
+pub struct TroopCurveExtractor;
+
+impl TroopCurveExtractor {
+    /// Create a new troop curve extractor.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Extractor for TroopCurveExtractor {
+    fn section(&self) -> &'static str {
+        "troop_curve"
+    }
+
+    fn extract(&self, input: &Value) -> Result<Section, ExtractError> {
+        let content = require_content(input)?;
+        let clock = Clock {
+            start_timestamp: require_u64_field(content, "Bts")?,
+            start_tick: require_u64_field(content, "Btk")?,
+        };
+
+        let mut markers = Vec::new();
+        let samples_value = content
+            .get("Samples")
+            .ok_or(ExtractError::MissingField { field: "Samples" })?;
+        for sample in indexed_array_values(samples_value, "Samples")? {
+            let tick = require_u64(sample, "T")?;
+            let count = require_u64(sample, "Cnt")?;
+            markers.push((tick, Marker::Sample(count)));
+        }
+        for entry in parse_events(content)? {
+            let marker = match &entry.event {
+                TimelineEvent::ReinforcementJoin(units) => Marker::Join(units.count),
+                TimelineEvent::ReinforcementLeave(units) => Marker::Leave(units.count),
+                _ => continue,
+            };
+            markers.push((entry.tick, marker));
+        }
+        let attacks = attack_bounds(content)?;
+        for attack in &attacks {
+            markers.push((attack.start_tick, Marker::AttackStart(attack.id)));
+            markers.push((attack.end_tick, Marker::AttackEnd(attack.id)));
+        }
+        markers.sort_by_key(|(tick, marker)| (*tick, marker.order()));
+
+        let points = build_points(&markers);
+        let segments = attacks
+            .iter()
+            .map(|attack| segment(attack, &points, &clock))
+            .collect::<Vec<_>>();
+
+        let first_tick = points.first().map(|point| point.tick);
+        let last_tick = points.last().map(|point| point.tick);
+        let mut section = Section::new();
+        section.insert("start_tick", Value::from(first_tick));
+        section.insert("end_tick", Value::from(last_tick));
+        section.insert(
+            "start_time",
+            Value::from(first_tick.and_then(|tick| clock.time(tick))),
+        );
+        section.insert(
+            "end_time",
+            Value::from(last_tick.and_then(|tick| clock.time(tick))),
+        );
+        section.insert(
+            "points",
+            Value::Array(points.iter().map(|point| point.to_json(&clock)).collect()),
+        );
+        section.insert("segments", Value::Array(segments));
+        Ok(section)
+    }
+}
+
+/// Maps ticks to Unix timestamps using the report's `Bts`/`Btk` pair.
+#[derive(Debug, Clone, Copy)]
+struct Clock {
+    start_timestamp: u64,
+    start_tick: u64,
+}
+
+impl Clock {
+    fn time(&self, tick: u64) -> Option<u64> {
+        tick.checked_sub(self.start_tick)
+            .map(|offset| self.start_timestamp + offset)
+    }
+}
+
+/// Tick bounds and opponent of one attack entry.
+#[derive(Debug, Clone)]
+struct AttackBounds<'a> {
+    id: &'a str,
+    player_id: Option<i64>,
+    start_tick: u64,
+    end_tick: u64,
+}
+
+/// Read attack tick bounds in opponent order; attacks without ticks are skipped.
+fn attack_bounds(content: &Map<String, Value>) -> Result<Vec<AttackBounds<'_>>, ExtractError> {
+    let attacks = require_attacks(content)?;
+
+    let mut bounds = Vec::with_capacity(attacks.len());
+    for (attack_key, attack) in attacks {
+        let (Some(start_tick), Some(end_tick)) = (
+            attack.get("Bts").and_then(Value::as_u64),
+            attack.get("Ets").and_then(Value::as_u64),
+        ) else {
+            continue;
+        };
+        let player_id = attack
+            .get("CIdt")
+            .and_then(|opponent| opponent.get("PId"))
+            .and_then(Value::as_i64);
+        bounds.push((
+            parse_attack_id(attack_key)?,
+            attack_key.as_str(),
+            AttackBounds {
+                id: attack_key,
+                player_id,
+                start_tick,
+                end_tick,
+            },
+        ));
+    }
+    sort_by_attack_order(&mut bounds);
+    Ok(bounds.into_iter().map(|(_, _, bounds)| bounds).collect())
+}
+
+/// Input to the series before counts are resolved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Marker<'a> {
+    Sample(u64),
+    Join(Option<u64>),
+    Leave(Option<u64>),
+    AttackStart(&'a str),
+    AttackEnd(&'a str),
+}
+
+impl Marker<'_> {
+    /// Order within a tick: attacks open first, events apply before the
+    /// sample that reflects them, and attacks close on the final count.
+    fn order(&self) -> u8 {
+        match self {
+            Marker::AttackStart(_) => 0,
+            Marker::Join(_) | Marker::Leave(_) => 1,
+            Marker::Sample(_) => 2,
+            Marker::AttackEnd(_) => 3,
+        }
+    }
+
+    fn source(&self) -> &'static str {
+        match self {
+            Marker::Sample(_) => "sample",
+            Marker::Join(_) => "reinforcement_join",
+            Marker::Leave(_) => "reinforcement_leave",
+            Marker::AttackStart(_) => "attack_start",
+            Marker::AttackEnd(_) => "attack_end",
+        }
+    }
+}
+
+/// A point on the reconstructed curve.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CurvePoint<'a> {
+    tick: u64,
+    marker: Marker<'a>,
+    count: Option<u64>,
+    delta: Option<i64>,
+}
+
+impl CurvePoint<'_> {
+    fn to_json(&self, clock: &Clock) -> Value {
+        let attack_id = match self.marker {
+            Marker::AttackStart(id) | Marker::AttackEnd(id) => Some(id),
+            _ => None,
+        };
+        json!({
+            "tick": self.tick,
+            "time": clock.time(self.tick),
+            "count": self.count,
+            "delta": self.delta,
+            "source": self.marker.source(),
+            "attack_id": attack_id,
+        })
+    }
+}
+
+/// Resolve counts along sorted markers. The count is unknown until the
+/// first sample; events without `Cnt` keep the running count.
+fn build_points<'a>(markers: &[(u64, Marker<'a>)]) -> Vec<CurvePoint<'a>> {
+    let mut current: Option<u64> = None;
+    let mut points = Vec::with_capacity(markers.len());
+    for &(tick, marker) in markers {
+        let previous = current;
+        let delta = match marker {
+            Marker::Sample(count) => {
+                current = Some(count);
+                previous.map(|previous| count as i64 - previous as i64)
+            }
+            Marker::Join(count) => {
+                current = current
+                    .zip(count)
+                    .map(|(current, count)| current + count)
+                    .or(current);
+                count.map(|count| count as i64)
+            }
+            Marker::Leave(count) => {
+                current = current
+                    .zip(count)
+                    .map(|(current, count)| current.saturating_sub(count))
+                    .or(current);
+                count.map(|count| -(count as i64))
+            }
+            Marker::AttackStart(_) | Marker::AttackEnd(_) => None,
+        };
+        points.push(CurvePoint {
+            tick,
+            marker,
+            count: current,
+            delta,
+        });
+    }
+    points
+}
+
+/// Summarize the part of the curve inside one attack's tick bounds.
+fn segment(attack: &AttackBounds<'_>, points: &[CurvePoint<'_>], clock: &Clock) -> Value {
+    let count_at = |marker: Marker<'_>| {
+        points
+            .iter()
+            .find(|point| point.marker == marker)
+            .and_then(|point| point.count)
+    };
+    let window = points
+        .iter()
+        .filter(|point| (attack.start_tick..=attack.end_tick).contains(&point.tick))
+        .collect::<Vec<_>>();
+    let counts = window.iter().filter_map(|point| point.count);
+    let event_total = |matches: fn(&Marker<'_>) -> bool| {
+        window
+            .iter()
+            .filter(|point| matches(&point.marker))
+            .filter_map(|point| point.delta)
+            .map(i64::unsigned_abs)
+            .sum::<u64>()
+    };
+
+    json!({
+        "attack_id": attack.id,
+        "player_id": attack.player_id,
+        "start_tick": attack.start_tick,
+        "end_tick": attack.end_tick,
+        "start_time": clock.time(attack.start_tick),
+        "end_time": clock.time(attack.end_tick),
+        "start_count": count_at(Marker::AttackStart(attack.id)),
+        "end_count": count_at(Marker::AttackEnd(attack.id)),
+        "min_count": counts.clone().min(),
+        "max_count": counts.max(),
+        "joined": event_total(|marker| matches!(marker, Marker::Join(_))),
+        "left": event_total(|marker| matches!(marker, Marker::Leave(_))),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mail_processor_sdk::Extractor;
+    use serde_json::{Value, json};
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn assist(tick: u64, event_type: u64, count: Option<u64>) -> Value {
+        let mut units = json!({ "PId": 7, "PName": "Ally", "Avatar": "null" });
+        if let Some(count) = count {
+            units["Cnt"] = json!(count);
+        }
+        json!({ "T": tick, "Et": event_type, "AssistUnits": units })
+    }
+
+    #[test]
+    fn troop_curve_merges_samples_events_and_attacks() {
+        let input = json!({
+            "body": {
+                "content": {
+                    "Bts": 1000,
+                    "Ets": 1030,
+                    "Btk": 500,
+                    "Samples": [
+                        1, { "T": 500, "Cnt": 100 },
+                        2, { "T": 520, "Cnt": 150 },
+                        3, { "T": 530, "Cnt": 40 }
+                    ],
+                    "Events": [
+                        1, assist(505, 18, Some(80)),
+                        2, assist(510, 26, Some(20)),
+                        3, assist(512, 26, None),
+                        4, { "T": 515, "Et": 16, "CastleHealing": { "Cnt": 5 } }
+                    ],
+                    "Attacks": {
+                        "9": { "Bts": 502, "Ets": 520, "CIdt": { "PId": -2 } },
+                        "11": { "Bts": 520, "Ets": 530 }
+                    }
+                }
+            }
+        });
+        let section = TroopCurveExtractor::new().extract(&input).expect("curve");
+        let fields = section.fields();
+
+        assert_eq!(fields["start_tick"], json!(500));
+        assert_eq!(fields["end_time"], json!(1030));
+        let points = fields["points"].as_array().expect("points");
+        let summary = points
+            .iter()
+            .map(|point| {
+                (
+                    point["tick"].as_u64().unwrap(),
+                    point["source"].as_str().unwrap(),
+                    point["count"].as_u64(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            [
+                (500, "sample", Some(100)),
+                (502, "attack_start", Some(100)),
+                (505, "reinforcement_join", Some(180)),
+                (510, "reinforcement_leave", Some(160)),
+                (512, "reinforcement_leave", Some(160)),
+                (520, "attack_start", Some(160)),
+                (520, "sample", Some(150)),
+                (520, "attack_end", Some(150)),
+                (530, "sample", Some(40)),
+                (530, "attack_end", Some(40)),
+            ]
+        );
+        assert_eq!(points[2]["time"], json!(1005));
+        assert_eq!(points[2]["delta"], json!(80));
+        assert_eq!(points[6]["delta"], json!(-10));
+        assert_eq!(points[7]["attack_id"], json!("9"));
+
+        let segments = fields["segments"].as_array().expect("segments");
+        assert_eq!(
+            segments[0],
+            json!({
+                "attack_id": "9",
+                "player_id": -2,
+                "start_tick": 502,
+                "end_tick": 520,
+                "start_time": 1002,
+                "end_time": 1020,
+                "start_count": 100,
+                "end_count": 150,
+                "min_count": 100,
+                "max_count": 180,
+                "joined": 80,
+                "left": 20
+            })
+        );
+        assert_eq!(segments[1]["attack_id"], json!("11"));
+        assert_eq!(segments[1]["start_count"], json!(160));
+        assert_eq!(segments[1]["min_count"], json!(40));
+    }
+
+    #[test]
+    fn troop_curve_leaves_counts_unknown_before_first_sample() {
+        let input = json!({
+            "body": {
+                "content": {
+                    "Bts": 1000,
+                    "Ets": 1000,
+                    "Btk": 500,
+                    "Samples": [1, { "T": 501, "Cnt": 10 }],
+                    "Events": [1, assist(499, 18, Some(5))],
+                    "Attacks": {}
+                }
+            }
+        });
+        let section = TroopCurveExtractor::new().extract(&input).expect("curve");
+        let points = section.fields()["points"]
+            .as_array()
+            .expect("points")
+            .clone();
+
+        assert_eq!(points[0]["count"], Value::Null);
+        assert_eq!(points[0]["time"], Value::Null);
+        assert_eq!(points[1]["count"], json!(10));
+        assert_eq!(points[1]["delta"], Value::Null);
+        assert_eq!(section.fields()["segments"], json!([]));
+    }
+
+    #[test]
+    fn troop_curve_requires_attacks() {
+        let input = json!({
+            "body": {
+                "content": {
+                    "Bts": 1000,
+                    "Ets": 1000,
+                    "Btk": 500,
+                    "Samples": [1, { "T": 501, "Cnt": 10 }],
+                    "Events": [1, assist(499, 18, Some(5))]
+                }
+            }
+        });
+        let err = TroopCurveExtractor::new().extract(&input).unwrap_err();
+        assert!(matches!(
+            err,
+            ExtractError::MissingField { field: "Attacks" }
+        ));
+    }
+
+    #[test]
+    fn roundtrip_troop_curve_extracts_sample() {
+        let sample_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../../samples/Battle/Persistent.Mail.1409019176893142331.json");
+        let json = fs::read_to_string(sample_path).expect("read sample");
+        let value: Value = serde_json::from_str(&json).expect("parse sample");
+        let section = TroopCurveExtractor::new()
+            .extract(&value)
+            .expect("extract sample");
+        let fields = section.fields();
+
+        assert_eq!(fields["start_tick"], json!(35058));
+        assert_eq!(fields["start_time"], json!(1768931412));
+        let segments = fields["segments"].as_array().expect("segments");
+        assert_eq!(segments.len(), 1);
+        assert_eq!(segments[0]["attack_id"], json!("10852801"));
+        assert_eq!(segments[0]["start_time"], json!(1768931412));
+        assert_eq!(segments[0]["end_time"], json!(1768931422));
+    }
+}
@@ -0,0 +1,190 @@
+//! Equipment string decoding for Battle commanders.
+
+use serde_json::{Value, json};
+
+// Equipment strings are encoded as `{slot:id[_craftNumber]:attributes}` entries
+// separated by commas, for example `{2:20004_379:32}`.
+//
+// Attributes are encoded as YX:
+// - Y: special talent troop type (0 when there is no special talent)
+// - X: iconic level (0 when there is no iconic level)
+//
+// Special talent (Y) mappings:
+// - 1: infantry
+// - 2: archer
+// - 3: cavalry
+// - 4: integration
+// - 5: leadership
+// - 16: engineering
+
+/// Parse a raw equipment string into per-slot entries sorted by slot.
+///
+/// Returns `None` when any entry is malformed so callers can drop the decoded
+/// view instead of guessing.
+pub(crate) fn parse_equipment(text: &str) -> Option<Vec<EquipmentSlot>> {
+    let trimmed = text.trim();
+    let inner = trimmed
+        .strip_prefix('{')
+        .and_then(|rest| rest.strip_suffix('}'))
+        .unwrap_or(trimmed)
+        .trim();
+    if inner.is_empty() {
+        return Some(Vec::new());
+    }
+
+    let mut slots = inner
+        .split(',')
+        .map(|entry| parse_entry(entry.trim()))
+        .collect::<Option<Vec<_>>>()?;
+    slots.sort_by_key(|slot| slot.slot);
+    Some(slots)
+}
+
+/// Decode an optional raw equipment string into a JSON array of slot entries.
+pub(crate) fn equipment_slots_value(text: Option<&str>) -> Value {
+    match text.and_then(parse_equipment) {
+        Some(slots) => Value::Array(slots.into_iter().map(EquipmentSlot::to_value).collect()),
+        None => Value::Null,
+    }
+}
+
+/// A single decoded equipment slot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct EquipmentSlot {
+    slot: u64,
+    id: u64,
+    craft: Option<u64>,
+    attributes: Option<u64>,
+}
+
+impl EquipmentSlot {
+    /// The special talent troop type code (Y), if any.
+    fn special_talent(self) -> Option<u64> {
+        self.attributes
+            .map(|attributes| attributes / 10)
+            .filter(|code| *code != 0)
+    }
+
+    /// The iconic level (X), if any.
+    fn iconic_level(self) -> Option<u64> {
+        self.attributes
+            .map(|attributes| attributes % 10)
+            .filter(|level| *level != 0)
+    }
+
+    fn to_value(self) -> Value {
+        json!({
+            "slot": self.slot,
+            "id": self.id,
+            "craft": self.craft,
+            "attributes": self.attributes,
+            "special_talent": self.special_talent().map(special_talent_label),
+            "iconic_level": self.iconic_level(),
+        })
+    }
+}
+
+fn parse_entry(entry: &str) -> Option<EquipmentSlot> {
+    let mut parts = entry.split(':');
+    let slot = parts.next()?.trim().parse().ok()?;
+    let item = parts.next()?.trim();
+    let (id, craft) = match item.split_once('_') {
+        Some((id, craft)) => (id.parse().ok()?, Some(craft.parse().ok()?)),
+        None => (item.parse().ok()?, None),
+    };
+    let attributes = match parts.next() {
+        Some(attributes) => Some(attributes.trim().parse().ok()?),
+        None => None,
+    };
+    if parts.next().is_some() {
+        return None;
+    }
+
+    Some(EquipmentSlot {
+        slot,
+        id,
+        craft,
+        attributes,
+    })
+}
+
+fn special_talent_label(code: u64) -> &'static str {
+    match code {
+        1 => "infantry",
+        2 => "archer",
+        3 => "cavalry",
+        4 => "integration",
+        5 => "leadership",
+        16 => "engineering",
+        _ => "unknown",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_equipment_reads_full_entries() {
+        let value = equipment_slots_value(Some("{2:20004_379:32,1:20001_101:1}"));
+        assert_eq!(
+            value,
+            json!([
+                {
+                    "slot": 1,
+                    "id": 20001,
+                    "craft": 101,
+                    "attributes": 1,
+                    "special_talent": null,
+                    "iconic_level": 1
+                },
+                {
+                    "slot": 2,
+                    "id": 20004,
+                    "craft": 379,
+                    "attributes": 32,
+                    "special_talent": "cavalry",
+                    "iconic_level": 2
+                }
+            ])
+        );
+    }
+
+    #[test]
+    fn parse_equipment_reads_engineering_talent_without_iconic() {
+        let slots = parse_equipment("{7:20121_80:160}").unwrap();
+        assert_eq!(slots[0].special_talent(), Some(16));
+        assert_eq!(slots[0].iconic_level(), None);
+        assert_eq!(slots[0].to_value()["special_talent"], json!("engineering"));
+    }
+
+    #[test]
+    fn parse_equipment_allows_missing_craft_and_attributes() {
+        let value = equipment_slots_value(Some("{1:200}"));
+        assert_eq!(
+            value,
+            json!([{
+                "slot": 1,
+                "id": 200,
+                "craft": null,
+                "attributes": null,
+                "special_talent": null,
+                "iconic_level": null
+            }])
+        );
+    }
+
+    #[test]
+    fn parse_equipment_accepts_empty_strings() {
+        assert_eq!(equipment_slots_value(Some("{}")), json!([]));
+        assert_eq!(equipment_slots_value(Some("")), json!([]));
+        assert_eq!(equipment_slots_value(None), Value::Null);
+    }
+
+    #[test]
+    fn parse_equipment_rejects_malformed_entries() {
+        assert!(parse_equipment("{1:abc_1:2}").is_none());
+        assert!(parse_equipment("{1}").is_none());
+        assert!(parse_equipment("{1:20001_1:2:3}").is_none());
+    }
+}
@@ -2,8 +2,14 @@
 
 //! Processor for Battle mail reports.
 
+mod analytics;
+mod armaments;
+mod classification;
 mod content;
+mod curve;
+mod equipment;
 mod metadata;
+mod npc;
 mod opponents;
 mod participants;
 mod player;
@@ -11,27 +17,45 @@ mod sender;
 mod summary;
 mod timeline;
 
-use mail_processor_sdk::{ProcessError, ProcessedMail, Processor};
+use mail_processor_sdk::{ProcessError, ProcessStats, ProcessedMail, Processor};
 use serde_json::Value;
 
-pub use mail_processor_sdk::{ExtractError, Section};
+pub use mail_processor_sdk::{ExtractError, Parallelism, Section};
 
 /// Process a decoded Battle mail with parallel extractors.
 pub fn process_parallel(input: &Value) -> Result<ProcessedMail, ProcessError> {
     processor().process_parallel(input)
 }
 
+/// Process a decoded Battle mail with parallel extractors and record per-section stats.
+pub fn process_parallel_with_stats(
+    input: &Value,
+) -> (Result<ProcessedMail, ProcessError>, ProcessStats) {
+    processor().process_parallel_with_stats(input)
+}
+
 /// Process a decoded Battle mail in extractor order.
 pub fn process_sequential(input: &Value) -> Result<ProcessedMail, ProcessError> {
     processor().process_sequential(input)
 }
 
-fn processor() -> Processor {
+/// Build the Battle processor with a bound on per-extractor worker threads.
+///
+/// The default processor uses [`Parallelism::default`].
+pub fn processor_with_parallelism(parallelism: Parallelism) -> Processor {
     Processor::new(vec![
         Box::new(metadata::MetadataExtractor::new()),
         Box::new(sender::SenderExtractor::new()),
         Box::new(summary::SummaryExtractor::new()),
-        Box::new(opponents::OpponentsExtractor::new()),
+        Box::new(opponents::OpponentsExtractor::new().with_parallelism(parallelism)),
         Box::new(timeline::TimelineExtractor::new()),
+        Box::new(analytics::AnalyticsExtractor::new()),
+        Box::new(curve::TroopCurveExtractor::new()),
+        Box::new(classification::ClassificationExtractor::new()),
+        Box::new(npc::NpcExtractor::new()),
     ])
 }
+
+fn processor() -> Processor {
+    processor_with_parallelism(Parallelism::default())
+}
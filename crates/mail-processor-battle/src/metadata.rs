@@ -1,10 +1,13 @@
 //! Metadata extractor for Battle mail.
 
+use std::collections::BTreeSet;
+
 use mail_processor_sdk::{ExtractError, Extractor, Section, require_string, require_u64};
-use serde_json::{Map, Value};
+use rokbattles_ids::Structure;
+use serde_json::{Map, Value, json};
 
 use crate::content::{require_child_object, require_content, require_string_field};
-use crate::player::extract_kingdom_id;
+use crate::player::{extract_kingdom_id, extract_supreme_strife, null_supreme_strife};
 
 /// Extracts top-level metadata fields from a Battle mail.
 #[derive(Debug, Default)]
@@ -30,6 +33,7 @@ impl Extractor for MetadataExtractor {
         let content = require_content(input)?;
         let mail_role = require_string_field(content, "Role")?;
         let kvk = resolve_kvk(&mail_role, content, server_id)?;
+        let season = resolve_season(content, server_id)?;
 
         let mut section = Section::new();
         section.insert("mail_id", Value::String(mail_id));
@@ -38,6 +42,7 @@ impl Extractor for MetadataExtractor {
         section.insert("server_id", Value::from(server_id));
         section.insert("mail_role", Value::String(mail_role));
         section.insert("kvk", Value::Bool(kvk));
+        section.insert("season", season);
         Ok(section)
     }
 }
@@ -67,6 +72,69 @@ fn resolve_kvk(
     Ok(kingdom_id.is_some_and(|id| id != server_id))
 }
 
+/// Resolve the season context of the report.
+///
+/// - `home_kingdom_id`: the sender's kingdom (`COSId`).
+/// - `battle_kingdom_id`: the kingdom map the battle happened on (`serverId`).
+/// - `opponent_kingdom_ids` / `cross_kingdom`: opponent kingdoms and whether
+///   any differs from the home kingdom; `cross_kingdom` is null when neither
+///   side reports a kingdom.
+/// - `supreme_strife`: the sender's Supreme Strife battle, team and round.
+/// - `ark`: Ark of Osiris structures either side fought at, or null.
+fn resolve_season(content: &Map<String, Value>, server_id: u64) -> Result<Value, ExtractError> {
+    let sender = content.get("SelfChar").and_then(Value::as_object);
+    let home_kingdom_id = match sender {
+        Some(sender) => extract_kingdom_id(sender)?,
+        None => None,
+    };
+    let supreme_strife = match sender {
+        Some(sender) => extract_supreme_strife(sender)?,
+        None => null_supreme_strife(),
+    };
+
+    let opponents = content
+        .get("Attacks")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flat_map(|attacks| attacks.values())
+        .filter_map(|attack| attack.get("CIdt").and_then(Value::as_object))
+        .collect::<Vec<_>>();
+    let mut opponent_kingdom_ids = BTreeSet::new();
+    for opponent in &opponents {
+        opponent_kingdom_ids.extend(extract_kingdom_id(opponent)?);
+    }
+    let cross_kingdom = home_kingdom_id
+        .filter(|_| !opponent_kingdom_ids.is_empty())
+        .map(|home| opponent_kingdom_ids.iter().any(|id| *id != home));
+
+    let ark_structures = sender
+        .into_iter()
+        .chain(opponents.iter().copied())
+        .filter_map(|player| player.get("ShId").and_then(Value::as_u64))
+        .map(Structure::from_id)
+        .filter(|structure| structure.is_ark())
+        .map(|structure| (structure.id(), structure.label()))
+        .collect::<BTreeSet<_>>();
+    let ark = if ark_structures.is_empty() {
+        Value::Null
+    } else {
+        let structures = ark_structures
+            .into_iter()
+            .map(|(id, label)| json!({ "id": id, "structure": label }))
+            .collect::<Vec<_>>();
+        json!({ "structures": structures })
+    };
+
+    Ok(json!({
+        "home_kingdom_id": home_kingdom_id,
+        "battle_kingdom_id": server_id,
+        "opponent_kingdom_ids": opponent_kingdom_ids,
+        "cross_kingdom": cross_kingdom,
+        "supreme_strife": supreme_strife,
+        "ark": ark,
+    }))
+}
+
 fn optional_bool_field(
     object: &Map<String, Value>,
     field: &'static str,
@@ -118,6 +186,61 @@ mod tests {
         assert_eq!(fields["server_id"], json!(55));
         assert_eq!(fields["mail_role"], json!("gsmp"));
         assert_eq!(fields["kvk"], json!(true));
+        assert_eq!(
+            fields["season"],
+            json!({
+                "home_kingdom_id": 10,
+                "battle_kingdom_id": 55,
+                "opponent_kingdom_ids": [],
+                "cross_kingdom": null,
+                "supreme_strife": { "battle_id": null, "team_id": null, "round": null },
+                "ark": null,
+            })
+        );
+    }
+
+    #[test]
+    fn metadata_extractor_resolves_season_context() {
+        let input = json!({
+            "id": "mail-1",
+            "time": 1234,
+            "receiver": "player-1",
+            "serverId": 1804,
+            "body": {
+                "content": {
+                    "Role": "gsmp",
+                    "SelfChar": {
+                        "COSId": 1804,
+                        "ShId": 24,
+                        "Titan": { "BattleId": "ss-1", "TeamId": 2, "Round": 3 }
+                    },
+                    "Attacks": {
+                        "1": { "CIdt": { "COSId": 2001, "ShId": 109 } },
+                        "2": { "CIdt": { "COSId": 1804, "ShId": 38 } },
+                        "3": { "CIdt": { "COSId": 2001 } }
+                    }
+                }
+            }
+        });
+        let section = MetadataExtractor::new().extract(&input).unwrap();
+        let season = &section.fields()["season"];
+        assert_eq!(season["home_kingdom_id"], json!(1804));
+        assert_eq!(season["battle_kingdom_id"], json!(1804));
+        assert_eq!(season["opponent_kingdom_ids"], json!([1804, 2001]));
+        assert_eq!(season["cross_kingdom"], json!(true));
+        assert_eq!(
+            season["supreme_strife"],
+            json!({ "battle_id": "ss-1", "team_id": 2, "round": 3 })
+        );
+        assert_eq!(
+            season["ark"],
+            json!({
+                "structures": [
+                    { "id": 24, "structure": "sky_altar" },
+                    { "id": 109, "structure": "obelisk" }
+                ]
+            })
+        );
     }
 
     #[test]
@@ -143,6 +266,10 @@ mod tests {
         assert_eq!(fields["mail_time"], json!(1755294123041275u64));
         assert_eq!(fields["mail_role"], json!("gsmp"));
         assert_eq!(fields["kvk"], json!(false));
+        assert_eq!(fields["season"]["home_kingdom_id"], json!(1804));
+        assert_eq!(fields["season"]["battle_kingdom_id"], json!(1804));
+        assert_eq!(fields["season"]["cross_kingdom"], json!(false));
+        assert_eq!(fields["season"]["ark"], json!(null));
     }
 
     #[test]
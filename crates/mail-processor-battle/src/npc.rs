@@ -0,0 +1,169 @@
+//! NPC totals extractor for Battle mail.
+
+use std::collections::BTreeMap;
+
+use mail_processor_sdk::{ExtractError, Extractor, Section};
+use serde_json::{Value, json};
+
+use crate::content::require_content;
+use crate::opponents::{extract_npc_loot, require_attacks};
+
+/// Totals NPC experience, loot and kills across every attack.
+///
+/// An NPC counts as killed when its remaining troop count (`Kill.Cnt`) is
+/// zero. Loot is summed per `(type, sub_type)`; names are attached later by
+/// dataset enrichment.
+#[derive(Debug, Default)]
+pub struct NpcExtractor;
+
+impl NpcExtractor {
+    /// Create a new NPC totals extractor.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Extractor for NpcExtractor {
+    fn section(&self) -> &'static str {
+        "npc"
+    }
+
+    fn extract(&self, input: &Value) -> Result<Section, ExtractError> {
+        let content = require_content(input)?;
+        let attacks = require_attacks(content)?;
+
+        let mut npc_attacks = 0u64;
+        let mut kills = 0u64;
+        let mut experience = 0u64;
+        let mut loot = BTreeMap::<(u64, u64), u64>::new();
+        for attack in attacks.values() {
+            let attack = attack.as_object().ok_or(ExtractError::InvalidFieldType {
+                field: "Attacks",
+                expected: "object",
+            })?;
+
+            experience += attack.get("NpcAtkExp").and_then(Value::as_u64).unwrap_or(0);
+            for entry in extract_npc_loot(attack)?.unwrap_or_default() {
+                let loot_type = entry["type"].as_u64().unwrap_or_default();
+                let sub_type = entry["sub_type"].as_u64().unwrap_or_default();
+                *loot.entry((loot_type, sub_type)).or_default() +=
+                    entry["value"].as_u64().unwrap_or_default();
+            }
+
+            let is_npc = attack
+                .get("CIdt")
+                .and_then(|opponent| opponent.get("NpcType"))
+                .is_some_and(|npc_type| !npc_type.is_null());
+            if is_npc {
+                npc_attacks += 1;
+                let remaining = attack.get("Kill").and_then(|kill| kill.get("Cnt"));
+                if remaining.and_then(Value::as_u64) == Some(0) {
+                    kills += 1;
+                }
+            }
+        }
+
+        let loot = loot
+            .into_iter()
+            .map(|((loot_type, sub_type), value)| {
+                json!({ "type": loot_type, "sub_type": sub_type, "value": value })
+            })
+            .collect();
+
+        let mut section = Section::new();
+        section.insert("attacks", Value::from(npc_attacks));
+        section.insert("kills", Value::from(kills));
+        section.insert("experience", Value::from(experience));
+        section.insert("loot", Value::Array(loot));
+        Ok(section)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::fs;
+    use std::path::PathBuf;
+
+    #[test]
+    fn npc_extractor_totals_loot_experience_and_kills() {
+        let input = json!({
+            "body": {
+                "content": {
+                    "Attacks": {
+                        "1": {
+                            "CIdt": { "NpcType": 38 },
+                            "NpcAtkExp": 100,
+                            "NpcKillLoot": [
+                                1, { "Type": 1, "SubType": 9, "Value": 50 },
+                                2, { "Type": 2, "SubType": 128, "Value": 3 }
+                            ],
+                            "Kill": { "Cnt": 0 }
+                        },
+                        "2": {
+                            "CIdt": { "NpcType": 38 },
+                            "NpcAtkExp": 40,
+                            "NpcKillLoot": [1, { "Type": 1, "SubType": 9, "Value": 25 }],
+                            "Kill": { "Cnt": 0 }
+                        },
+                        "3": {
+                            "CIdt": { "NpcType": 110 },
+                            "NpcAtkExp": 0,
+                            "Kill": { "Cnt": 5000 }
+                        },
+                        "4": {
+                            "CIdt": { "NpcType": null },
+                            "Kill": { "Cnt": 0 }
+                        }
+                    }
+                }
+            }
+        });
+
+        let section = NpcExtractor::new().extract(&input).expect("extract npc");
+        let fields = section.fields();
+        assert_eq!(fields["attacks"], json!(3));
+        assert_eq!(fields["kills"], json!(2));
+        assert_eq!(fields["experience"], json!(140));
+        assert_eq!(
+            fields["loot"],
+            json!([
+                { "type": 1, "sub_type": 9, "value": 75 },
+                { "type": 2, "sub_type": 128, "value": 3 }
+            ])
+        );
+    }
+
+    #[test]
+    fn npc_extractor_is_empty_without_npcs() {
+        let input = json!({ "body": { "content": { "Attacks": {} } } });
+        let section = NpcExtractor::new().extract(&input).expect("extract npc");
+        let fields = section.fields();
+        assert_eq!(fields["attacks"], json!(0));
+        assert_eq!(fields["kills"], json!(0));
+        assert_eq!(fields["experience"], json!(0));
+        assert_eq!(fields["loot"], json!([]));
+    }
+
+    #[test]
+    fn roundtrip_npc_extracts_sample() {
+        let sample_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../../samples/Battle/Persistent.Mail.1409019176893142331.json");
+        let json = fs::read_to_string(sample_path).expect("read sample");
+        let value: Value = serde_json::from_str(&json).expect("parse sample");
+        let section = NpcExtractor::new().extract(&value).expect("extract sample");
+        let fields = section.fields();
+        assert_eq!(fields["attacks"], json!(1));
+        assert_eq!(fields["kills"], json!(1));
+        assert_eq!(fields["experience"], json!(6650));
+        assert_eq!(
+            fields["loot"],
+            json!([
+                { "type": 2, "sub_type": 128, "value": 38 },
+                { "type": 2, "sub_type": 417, "value": 2 },
+                { "type": 2, "sub_type": 7005, "value": 66 }
+            ])
+        );
+    }
+}
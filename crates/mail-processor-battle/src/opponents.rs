@@ -1,6 +1,6 @@
 //! Opponent extractor for Battle mail.
 
-use mail_processor_sdk::{ExtractError, Extractor, Section, indexed_array_values};
+use mail_processor_sdk::{ExtractError, Extractor, Parallelism, Section, indexed_array_values};
 use serde_json::{Map, Value, json};
 
 use crate::content::{require_child_object, require_content, require_u64_field};
@@ -8,13 +8,23 @@ use crate::participants::extract_participants;
 use crate::player::extract_player_fields;
 
 /// Extracts opponent details from each attack entry.
+///
+/// Attacks are extracted on a bounded worker pool; see [`Parallelism`].
 #[derive(Debug, Default)]
-pub struct OpponentsExtractor;
+pub struct OpponentsExtractor {
+    parallelism: Parallelism,
+}
 
 impl OpponentsExtractor {
     /// Create a new opponents extractor.
     pub fn new() -> Self {
-        Self
+        Self::default()
+    }
+
+    /// Bound the worker threads used to extract attacks.
+    pub fn with_parallelism(mut self, parallelism: Parallelism) -> Self {
+        self.parallelism = parallelism;
+        self
     }
 }
 
@@ -25,34 +35,17 @@ impl Extractor for OpponentsExtractor {
 
     fn extract(&self, input: &Value) -> Result<Section, ExtractError> {
         let content = require_content(input)?;
-        let attacks = require_attacks(content)?;
-
-        let mut results = Vec::with_capacity(attacks.len());
-        std::thread::scope(|scope| {
-            let mut handles = Vec::with_capacity(attacks.len());
-            for (attack_key, attack) in attacks {
-                let attack_key = attack_key.to_string();
-                let handle = scope.spawn(move || extract_attack_entry(attack_key, attack));
-                handles.push(handle);
-            }
+        let attacks = require_attacks(content)?.iter().collect::<Vec<_>>();
 
-            for handle in handles {
-                let result = handle.join().map_err(|_| ExtractError::InvalidFieldType {
-                    field: "Attacks",
-                    expected: "non-panicking extraction",
-                })?;
-                results.push(result?);
-            }
-            Ok::<(), ExtractError>(())
-        })?;
+        let mut results = self
+            .parallelism
+            .map(&attacks, |(attack_key, attack)| {
+                extract_attack_entry(attack_key.to_string(), attack)
+            })
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
 
-        results.sort_by(
-            |(attack_id_a, attack_key_a, _), (attack_id_b, attack_key_b, _)| {
-                attack_id_a
-                    .cmp(attack_id_b)
-                    .then_with(|| attack_key_a.cmp(attack_key_b))
-            },
-        );
+        sort_by_attack_order(&mut results);
         let entries = results.into_iter().map(|(_, _, value)| value).collect();
 
         Ok(Section::from_array(entries))
@@ -60,7 +53,9 @@ impl Extractor for OpponentsExtractor {
 }
 
 /// Read the attacks map from the content object.
-fn require_attacks(content: &Map<String, Value>) -> Result<&Map<String, Value>, ExtractError> {
+pub(crate) fn require_attacks(
+    content: &Map<String, Value>,
+) -> Result<&Map<String, Value>, ExtractError> {
     let value = content
         .get("Attacks")
         .ok_or(ExtractError::MissingField { field: "Attacks" })?;
@@ -70,8 +65,18 @@ fn require_attacks(content: &Map<String, Value>) -> Result<&Map<String, Value>,
     })
 }
 
+/// Sort `(attack id, attack key, item)` entries by numeric id, then raw key.
+///
+/// Keys sharing an id (such as `79272307` and `79272307_1`) keep a stable order.
+pub(crate) fn sort_by_attack_order<K: AsRef<str>, T>(entries: &mut [(u64, K, T)]) {
+    entries.sort_by(|(id_a, key_a, _), (id_b, key_b, _)| {
+        id_a.cmp(id_b)
+            .then_with(|| key_a.as_ref().cmp(key_b.as_ref()))
+    });
+}
+
 /// Parse the attack identifier from the attack map key.
-fn parse_attack_id(attack_id: &str) -> Result<u64, ExtractError> {
+pub(crate) fn parse_attack_id(attack_id: &str) -> Result<u64, ExtractError> {
     let end = attack_id
         .char_indices()
         .find(|&(_, ch)| !ch.is_ascii_digit())
@@ -269,7 +274,9 @@ fn null_battle_result() -> Value {
 }
 
 /// Extract NPC loot drops when present on the attack payload.
-fn extract_npc_loot(attack: &Map<String, Value>) -> Result<Option<Vec<Value>>, ExtractError> {
+pub(crate) fn extract_npc_loot(
+    attack: &Map<String, Value>,
+) -> Result<Option<Vec<Value>>, ExtractError> {
     let value = match attack.get("NpcKillLoot") {
         None | Some(Value::Null) => return Ok(None),
         Some(value) => value,
@@ -464,6 +471,7 @@ mod tests {
             json!({ "id": 1, "name": "AllianceOne", "abbreviation": "ONE" })
         );
         assert_eq!(opponents[0]["alliance_building_id"], json!(3));
+        assert_eq!(opponents[0]["alliance_building"], json!("stronghold"));
         assert_eq!(
             opponents[0]["castle"],
             json!({
@@ -477,6 +485,7 @@ mod tests {
         assert_eq!(opponents[0]["camp_id"], json!(null));
         assert_eq!(opponents[0]["rally"], json!(false));
         assert_eq!(opponents[0]["structure_id"], json!(22));
+        assert_eq!(opponents[0]["structure"], json!("shrine_of_war"));
         assert!(opponents[0]["commanders"]["primary"]["id"].is_null());
         assert!(opponents[0]["commanders"]["secondary"]["id"].is_null());
         assert_eq!(opponents[0]["app_id"], json!(2104267));
@@ -485,19 +494,18 @@ mod tests {
             .as_array()
             .expect("participants array");
         assert_eq!(participants.len(), 1);
+        assert_eq!(participants[0]["participant_id"], json!(5));
+        assert_eq!(participants[0]["player_id"], json!(500));
+        assert_eq!(participants[0]["player_name"], json!("OT-One"));
+        assert_eq!(participants[0]["alliance"], json!({ "abbreviation": "OT" }));
+        assert_eq!(participants[0]["commanders"]["primary"]["id"], json!(1));
+        assert_eq!(participants[0]["commanders"]["primary"]["level"], json!(2));
+        assert_eq!(participants[0]["commanders"]["secondary"]["id"], json!(3));
         assert_eq!(
-            participants[0],
-            json!({
-                "participant_id": 5,
-                "player_id": 500,
-                "player_name": "OT-One",
-                "alliance": { "abbreviation": "OT" },
-                "commanders": {
-                    "primary": { "id": 1, "level": 2 },
-                    "secondary": { "id": 3, "level": 4 },
-                }
-            })
+            participants[0]["commanders"]["secondary"]["level"],
+            json!(4)
         );
+        assert_eq!(participants[0]["extra"], json!({}));
         assert_eq!(opponents[1]["attack"]["id"], json!("20"));
         assert_eq!(opponents[1]["start_tick"], json!(210));
         assert_eq!(opponents[1]["end_tick"], json!(220));
@@ -509,6 +517,7 @@ mod tests {
             json!({ "id": 2, "name": "AllianceTwo", "abbreviation": "TWO" })
         );
         assert_eq!(opponents[1]["alliance_building_id"], json!(11));
+        assert_eq!(opponents[1]["alliance_building"], json!("horse_fort"));
         assert_eq!(
             opponents[1]["castle"],
             json!({
@@ -522,9 +531,11 @@ mod tests {
         assert_eq!(opponents[1]["camp_id"], json!(9));
         assert_eq!(opponents[1]["rally"], json!(true));
         assert_eq!(opponents[1]["structure_id"], json!(51));
+        assert_eq!(opponents[1]["structure"], json!("level_7_pass"));
         assert!(opponents[1]["commanders"]["primary"]["id"].is_null());
         assert!(opponents[1]["commanders"]["secondary"]["id"].is_null());
         assert_eq!(opponents[1]["app_id"], json!(8518744));
+        assert_eq!(opponents[1]["app_client"], json!("korean"));
         assert_eq!(opponents[1]["app_uid"], json!(399975));
         assert_eq!(
             opponents[1]["frame_url"],
@@ -534,19 +545,21 @@ mod tests {
             .as_array()
             .expect("participants array");
         assert_eq!(participants.len(), 1);
+        assert_eq!(participants[0]["participant_id"], json!(-2));
+        assert_eq!(participants[0]["player_id"], json!(600));
+        assert_eq!(participants[0]["player_name"], json!("OT-Two"));
         assert_eq!(
-            participants[0],
-            json!({
-                "participant_id": -2,
-                "player_id": 600,
-                "player_name": "OT-Two",
-                "alliance": { "abbreviation": "OT2" },
-                "commanders": {
-                    "primary": { "id": 5, "level": 6 },
-                    "secondary": { "id": 7, "level": 8 },
-                }
-            })
+            participants[0]["alliance"],
+            json!({ "abbreviation": "OT2" })
         );
+        assert_eq!(participants[0]["commanders"]["primary"]["id"], json!(5));
+        assert_eq!(participants[0]["commanders"]["primary"]["level"], json!(6));
+        assert_eq!(participants[0]["commanders"]["secondary"]["id"], json!(7));
+        assert_eq!(
+            participants[0]["commanders"]["secondary"]["level"],
+            json!(8)
+        );
+        assert_eq!(participants[0]["extra"], json!({}));
     }
 
     #[test]
@@ -590,6 +603,16 @@ mod tests {
         assert!(matches!(err, ExtractError::MissingField { field: "Bts" }));
     }
 
+    #[test]
+    fn sort_by_attack_order_uses_numeric_id_then_key() {
+        let mut entries = vec![(20, "20", 'c'), (3, "3_1", 'b'), (3, "3", 'a')];
+        sort_by_attack_order(&mut entries);
+        assert_eq!(
+            entries.iter().map(|(_, _, item)| *item).collect::<String>(),
+            "abc"
+        );
+    }
+
     #[test]
     fn parse_attack_id_allows_suffixes() {
         assert_eq!(parse_attack_id("79272307").unwrap(), 79272307);
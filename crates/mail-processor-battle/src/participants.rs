@@ -4,8 +4,16 @@ use mail_processor_sdk::ExtractError;
 use serde_json::{Map, Value, json};
 
 use crate::content::require_string_field;
+use crate::player::{extract_commanders, is_commander_field};
+
+/// Participant keys read into named output fields.
+const PARTICIPANT_FIELDS: [&str; 3] = ["PId", "PName", "Abbr"];
 
 /// Extract participant objects from the specified field.
+///
+/// Commanders use the same extraction as the main player, so skills, relics,
+/// equipment and armaments appear whenever the payload includes them. Keys
+/// that are not read are passed through unchanged under `extra`.
 pub(crate) fn extract_participants(
     container: &Map<String, Value>,
     field: &'static str,
@@ -38,19 +46,21 @@ pub(crate) fn extract_participants(
         let player_name = require_string_field(participant, "PName")?;
         // Some reports omit alliance abbreviations for participants; default to empty.
         let alliance_abbr = optional_string_field(participant, "Abbr")?.unwrap_or_default();
-        let primary_id = optional_u64_field(participant, "HId")?;
-        let primary_level = optional_u64_field(participant, "HLv")?;
-        let secondary_id = optional_u64_field(participant, "HId2")?;
-        let secondary_level = optional_u64_field(participant, "HLv2")?;
+        let commanders = extract_commanders(participant)?;
+        let extra = participant
+            .iter()
+            .filter(|(key, _)| {
+                !PARTICIPANT_FIELDS.contains(&key.as_str()) && !is_commander_field(key)
+            })
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect::<Map<_, _>>();
         entries.push(json!({
             "participant_id": participant_id,
             "player_id": player_id,
             "player_name": player_name,
             "alliance": { "abbreviation": alliance_abbr },
-            "commanders": {
-                "primary": { "id": primary_id, "level": primary_level },
-                "secondary": { "id": secondary_id, "level": secondary_level },
-            },
+            "commanders": commanders,
+            "extra": extra,
         }));
     }
 
@@ -103,27 +113,26 @@ fn optional_string_field(
     }
 }
 
-fn optional_u64_field(
-    object: &Map<String, Value>,
-    field: &'static str,
-) -> Result<Option<u64>, ExtractError> {
-    match object.get(field) {
-        None | Some(Value::Null) => Ok(None),
-        Some(value) => value
-            .as_u64()
-            .map(Some)
-            .ok_or(ExtractError::InvalidFieldType {
-                field,
-                expected: "unsigned integer",
-            }),
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
     use serde_json::json;
 
+    fn commander(id: u64, level: u64) -> Value {
+        json!({
+            "id": id,
+            "level": level,
+            "formation": null,
+            "awakened": null,
+            "star_level": null,
+            "equipment": null,
+            "equipment_slots": null,
+            "skills": null,
+            "relics": null,
+            "armaments": null,
+        })
+    }
+
     #[test]
     fn extract_participants_reads_entries() {
         let input = json!({
@@ -159,9 +168,10 @@ mod tests {
                     "player_name": "Alpha",
                     "alliance": { "abbreviation": "AA" },
                     "commanders": {
-                        "primary": { "id": 10, "level": 20 },
-                        "secondary": { "id": 11, "level": 21 },
-                    }
+                        "primary": commander(10, 20),
+                        "secondary": commander(11, 21),
+                    },
+                    "extra": {}
                 },
                 {
                     "participant_id": 3,
@@ -169,9 +179,10 @@ mod tests {
                     "player_name": "Beta",
                     "alliance": { "abbreviation": "BB" },
                     "commanders": {
-                        "primary": { "id": 12, "level": 22 },
-                        "secondary": { "id": 13, "level": 23 },
-                    }
+                        "primary": commander(12, 22),
+                        "secondary": commander(13, 23),
+                    },
+                    "extra": {}
                 }
             ])
         );
@@ -202,14 +213,61 @@ mod tests {
                     "player_name": "Alpha",
                     "alliance": { "abbreviation": "" },
                     "commanders": {
-                        "primary": { "id": 10, "level": 20 },
-                        "secondary": { "id": 11, "level": 21 },
-                    }
+                        "primary": commander(10, 20),
+                        "secondary": commander(11, 21),
+                    },
+                    "extra": {}
                 }
             ])
         );
     }
 
+    #[test]
+    fn extract_participants_reads_full_commanders_and_extra_keys() {
+        let input = json!({
+            "OTs": {
+                "7": {
+                    "PId": 100,
+                    "PName": "Alpha",
+                    "Abbr": "AA",
+                    "HId": 10,
+                    "HLv": 60,
+                    "HSt": 5,
+                    "HAw": true,
+                    "HSS": [
+                        { "SkillId": 1001, "SkillLevel": 5 },
+                        { "SkillId": 1002, "SkillLevel": 4 }
+                    ],
+                    "HClt": [3, 2],
+                    "HId2": 11,
+                    "HLv2": 59,
+                    "HSS2": [{ "SkillId": 2001, "SkillLevel": 1 }],
+                    "Power": 12345,
+                    "COSId": 1804
+                }
+            }
+        });
+
+        let participants = extract_participants(input.as_object().unwrap(), "OTs").unwrap();
+        let participant = &participants[0];
+        let primary = &participant["commanders"]["primary"];
+        assert_eq!(primary["star_level"], json!(5));
+        assert_eq!(primary["awakened"], json!(true));
+        assert_eq!(
+            primary["skills"],
+            json!([{ "id": 1001, "level": 5 }, { "id": 1002, "level": 4 }])
+        );
+        assert_eq!(primary["relics"], json!([{ "id": 3, "level": 2 }]));
+        assert_eq!(
+            participant["commanders"]["secondary"]["skills"],
+            json!([{ "id": 2001, "level": 1 }])
+        );
+        assert_eq!(
+            participant["extra"],
+            json!({ "Power": 12345, "COSId": 1804 })
+        );
+    }
+
     #[test]
     fn extract_participants_allows_missing_field() {
         let input = json!({});
@@ -1,19 +1,12 @@
 //! Shared player extraction helpers for Battle mail.
 
 use mail_processor_sdk::{ExtractError, indexed_array_values};
+use rokbattles_ids::{AllianceBuilding, AppClient, Structure};
 use serde_json::{Map, Value, json};
 
+use crate::armaments::optional_armaments_field;
 use crate::content::{require_child_object, require_string_field, require_u64_field};
-
-// AppUid app_id prefixes:
-// - 2104267: international client
-// - 3724753: chinese client
-// - 6626468: vietnamese client (gamota)
-// - 8518744: korean client
-// - 8529460: japanese client
-// - 9105753: chinese client (huawei)
-// - 9602340: chinese client (tw)
-const APP_ID_INTERNATIONAL: u64 = 2_104_267;
+use crate::equipment::equipment_slots_value;
 
 /// Extract the common player fields from a Battle character object.
 pub(crate) fn extract_player_fields(
@@ -25,10 +18,6 @@ pub(crate) fn extract_player_fields(
     let alliance_id = require_u64_field(player, "AId")?;
     let alliance_name = require_string_field(player, "AName")?;
     let alliance_abbr = require_string_field(player, "Abbr")?;
-    // Alliance building id (AbT) mappings:
-    // - 1: flag
-    // - 3: stronghold
-    // - 11: horse fort (troy kvk)
     let alliance_building_id = optional_u64_field(player, "AbT")?;
     let castle_pos = require_child_object(player, "CastlePos")?;
     let castle_x = require_number_value(castle_pos, "X")?;
@@ -39,15 +28,6 @@ pub(crate) fn extract_player_fields(
     let tracking_key = optional_string_field(player, "CTK")?.unwrap_or_default();
     let camp_id = optional_u64_field(player, "SideId")?;
     let rally = optional_bool_field(player, "IsRally")?;
-    // Structure id (ShId) mappings:
-    // - 22: shrine of war (Ark)
-    // - 23: shrine of life (Ark)
-    // - 24: sky altar (Ark)
-    // - 25: ? (Ark)
-    // - 26: outpost of iset (Ark)
-    // - 38: Great Ziggurat (KVK)
-    // - 51: Lvl 7 Pass (KVK)
-    // - 109: obelisk (Ark)
     let structure_id = optional_u64_field(player, "ShId")?;
     let commanders = extract_commanders(player)?;
     let (app_id, app_uid) = extract_app_identity(player)?;
@@ -73,6 +53,12 @@ pub(crate) fn extract_player_fields(
         "alliance_building_id".to_string(),
         alliance_building_id.map(Value::from).unwrap_or(Value::Null),
     );
+    fields.insert(
+        "alliance_building".to_string(),
+        id_label(alliance_building_id, |id| {
+            AllianceBuilding::from_id(id).label()
+        }),
+    );
     fields.insert(
         "castle".to_string(),
         json!({
@@ -95,11 +81,19 @@ pub(crate) fn extract_player_fields(
         "structure_id".to_string(),
         structure_id.map(Value::from).unwrap_or(Value::Null),
     );
+    fields.insert(
+        "structure".to_string(),
+        id_label(structure_id, |id| Structure::from_id(id).label()),
+    );
     fields.insert("commanders".to_string(), commanders);
     fields.insert(
         "app_id".to_string(),
         app_id.map(Value::from).unwrap_or(Value::Null),
     );
+    fields.insert(
+        "app_client".to_string(),
+        id_label(app_id, |id| AppClient::from_id(id).label()),
+    );
     fields.insert(
         "app_uid".to_string(),
         app_uid.map(Value::from).unwrap_or(Value::Null),
@@ -110,6 +104,11 @@ pub(crate) fn extract_player_fields(
     Ok(fields)
 }
 
+/// Resolve an optional game id into its label, keeping null for missing ids.
+fn id_label(id: Option<u64>, label: fn(u64) -> &'static str) -> Value {
+    id.map(|id| Value::from(label(id))).unwrap_or(Value::Null)
+}
+
 /// Extract kingdom id from `COSId`.
 pub(crate) fn extract_kingdom_id(player: &Map<String, Value>) -> Result<Option<u64>, ExtractError> {
     optional_u64_field(player, "COSId")
@@ -184,7 +183,7 @@ fn extract_app_identity(
                 Ok((Some(app_id), Some(app_uid)))
             } else {
                 let app_uid = parse_app_uid_number(&text, "unsigned integer")?;
-                Ok((Some(APP_ID_INTERNATIONAL), Some(app_uid)))
+                Ok((Some(AppClient::International.id()), Some(app_uid)))
             }
         }
         None => Ok((None, None)),
@@ -259,7 +258,7 @@ fn optional_string_field(
 }
 
 /// Extract Supreme Strife (Titan) details for the player.
-fn extract_supreme_strife(player: &Map<String, Value>) -> Result<Value, ExtractError> {
+pub(crate) fn extract_supreme_strife(player: &Map<String, Value>) -> Result<Value, ExtractError> {
     let value = match player.get("Titan") {
         None | Some(Value::Null) => return Ok(null_supreme_strife()),
         Some(value) => value,
@@ -280,7 +279,7 @@ fn extract_supreme_strife(player: &Map<String, Value>) -> Result<Value, ExtractE
 }
 
 /// Build a null-filled Supreme Strife entry when data is missing.
-fn null_supreme_strife() -> Value {
+pub(crate) fn null_supreme_strife() -> Value {
     json!({
         "battle_id": Value::Null,
         "team_id": Value::Null,
@@ -288,7 +287,8 @@ fn null_supreme_strife() -> Value {
     })
 }
 
-fn extract_commanders(player: &Map<String, Value>) -> Result<Value, ExtractError> {
+/// Extract primary and secondary commander details from a player or participant.
+pub(crate) fn extract_commanders(player: &Map<String, Value>) -> Result<Value, ExtractError> {
     let primary = extract_commander(player, &CommanderFieldSet::PRIMARY)?;
     let secondary = extract_commander(player, &CommanderFieldSet::SECONDARY)?;
     Ok(json!({ "primary": primary, "secondary": secondary }))
@@ -329,6 +329,26 @@ impl CommanderFieldSet {
         relics: "HClt2",
         armaments: None,
     };
+
+    fn contains(&self, key: &str) -> bool {
+        [
+            self.id,
+            self.level,
+            self.formation,
+            self.awakened,
+            self.star,
+            self.equipment,
+            self.skills,
+            self.relics,
+        ]
+        .contains(&key)
+            || self.armaments == Some(key)
+    }
+}
+
+/// Whether a key is read by [`extract_commanders`].
+pub(crate) fn is_commander_field(key: &str) -> bool {
+    CommanderFieldSet::PRIMARY.contains(key) || CommanderFieldSet::SECONDARY.contains(key)
 }
 
 fn extract_commander(
@@ -341,6 +361,7 @@ fn extract_commander(
     let awakened = optional_bool_field(player, fields.awakened)?;
     let star_level = optional_u64_field(player, fields.star)?;
     let equipment = optional_string_field(player, fields.equipment)?;
+    let equipment_slots = equipment_slots_value(equipment.as_deref());
     let skills = optional_skills_field(player, fields.skills)?;
     let relics = optional_relics_field(player, fields.relics)?;
     let armaments = match fields.armaments {
@@ -355,6 +376,7 @@ fn extract_commander(
         "awakened": awakened,
         "star_level": star_level,
         "equipment": equipment,
+        "equipment_slots": equipment_slots,
         "skills": skills,
         "relics": relics,
         "armaments": armaments,
@@ -417,40 +439,6 @@ fn optional_relics_field(
     Ok(Value::Array(relics))
 }
 
-fn optional_armaments_field(
-    player: &Map<String, Value>,
-    field: &'static str,
-) -> Result<Value, ExtractError> {
-    let value = match player.get(field) {
-        None | Some(Value::Null) => return Ok(Value::Null),
-        Some(value) => value,
-    };
-    let map = value.as_object().ok_or(ExtractError::InvalidFieldType {
-        field,
-        expected: "object",
-    })?;
-
-    let mut entries = Vec::with_capacity(map.len());
-    for (key, value) in map {
-        let id = key
-            .parse::<u64>()
-            .map_err(|_| ExtractError::InvalidFieldType {
-                field,
-                expected: "numeric object key",
-            })?;
-        let value = value.as_object().ok_or(ExtractError::InvalidFieldType {
-            field,
-            expected: "object",
-        })?;
-        let affix = require_string_field(value, "Affix")?;
-        let buffs = require_string_field(value, "Buffs")?;
-        entries.push(json!({ "id": id, "affix": affix, "buffs": buffs }));
-    }
-
-    entries.sort_by_key(|entry| entry["id"].as_u64().unwrap_or_default());
-    Ok(Value::Array(entries))
-}
-
 /// Parse the avatar field into avatar and frame URLs.
 pub(crate) fn parse_avatar(player: &Map<String, Value>) -> Result<(Value, Value), ExtractError> {
     let value = player
@@ -725,9 +713,24 @@ mod tests {
                     "awakened": true,
                     "star_level": 4,
                     "equipment": "{1:200}",
+                    "equipment_slots": [{
+                        "slot": 1,
+                        "id": 200,
+                        "craft": null,
+                        "attributes": null,
+                        "special_talent": null,
+                        "iconic_level": null
+                    }],
                     "skills": [{ "id": 111, "level": 3 }],
                     "relics": [{ "id": 10001, "level": 2 }],
-                    "armaments": [{ "id": 1, "affix": "-1", "buffs": "buffs-1" }]
+                    "armaments": [{
+                        "id": 1,
+                        "kind": "scroll",
+                        "affix": "-1",
+                        "buffs": "buffs-1",
+                        "inscriptions": [],
+                        "stats": null
+                    }]
                 },
                 "secondary": {
                     "id": 502,
@@ -736,6 +739,14 @@ mod tests {
                     "awakened": false,
                     "star_level": 5,
                     "equipment": "{2:201}",
+                    "equipment_slots": [{
+                        "slot": 2,
+                        "id": 201,
+                        "craft": null,
+                        "attributes": null,
+                        "special_talent": null,
+                        "iconic_level": null
+                    }],
                     "skills": [{ "id": 222, "level": 5 }],
                     "relics": [{ "id": 20001, "level": 5 }],
                     "armaments": null
@@ -749,7 +760,11 @@ mod tests {
         let mut player = base_player();
         player.insert("AppUid".to_string(), json!("103134073"));
         let fields = extract_player_fields(&player).unwrap();
-        assert_eq!(fields.get("app_id"), Some(&json!(APP_ID_INTERNATIONAL)));
+        assert_eq!(
+            fields.get("app_id"),
+            Some(&json!(AppClient::International.id()))
+        );
+        assert_eq!(fields.get("app_client"), Some(&json!("international")));
         assert_eq!(fields.get("app_uid"), Some(&json!(103134073)));
     }
 
@@ -806,6 +821,7 @@ mod tests {
                     "awakened": null,
                     "star_level": null,
                     "equipment": null,
+                    "equipment_slots": null,
                     "skills": null,
                     "relics": null,
                     "armaments": null
@@ -817,6 +833,7 @@ mod tests {
                     "awakened": null,
                     "star_level": null,
                     "equipment": null,
+                    "equipment_slots": null,
                     "skills": null,
                     "relics": null,
                     "armaments": null
@@ -100,6 +100,7 @@ mod tests {
             json!({ "id": 42, "name": "Alliance", "abbreviation": "AL" })
         );
         assert_eq!(fields["alliance_building_id"], json!(1));
+        assert_eq!(fields["alliance_building"], json!("flag"));
         assert_eq!(
             fields["castle"],
             json!({
@@ -113,10 +114,12 @@ mod tests {
         assert_eq!(fields["camp_id"], json!(null));
         assert_eq!(fields["rally"], json!(true));
         assert_eq!(fields["structure_id"], json!(25));
+        assert_eq!(fields["structure"], json!("ark_unidentified"));
         assert!(fields["commanders"]["primary"]["id"].is_null());
         assert!(fields["commanders"]["secondary"]["id"].is_null());
         assert!(fields["commanders"]["primary"]["armaments"].is_null());
         assert_eq!(fields["app_id"], json!(8518744));
+        assert_eq!(fields["app_client"], json!("korean"));
         assert_eq!(fields["app_uid"], json!(123));
         assert_eq!(
             fields["avatar_url"],
@@ -127,19 +130,18 @@ mod tests {
             .as_array()
             .expect("participants array");
         assert_eq!(participants.len(), 1);
+        assert_eq!(participants[0]["participant_id"], json!(1));
+        assert_eq!(participants[0]["player_id"], json!(10));
+        assert_eq!(participants[0]["player_name"], json!("Sender"));
+        assert_eq!(participants[0]["alliance"], json!({ "abbreviation": "AL" }));
+        assert_eq!(participants[0]["commanders"]["primary"]["id"], json!(11));
+        assert_eq!(participants[0]["commanders"]["primary"]["level"], json!(20));
+        assert_eq!(participants[0]["commanders"]["secondary"]["id"], json!(12));
         assert_eq!(
-            participants[0],
-            json!({
-                "participant_id": 1,
-                "player_id": 10,
-                "player_name": "Sender",
-                "alliance": { "abbreviation": "AL" },
-                "commanders": {
-                    "primary": { "id": 11, "level": 20 },
-                    "secondary": { "id": 12, "level": 21 },
-                }
-            })
+            participants[0]["commanders"]["secondary"]["level"],
+            json!(21)
         );
+        assert_eq!(participants[0]["extra"], json!({}));
     }
 
     #[test]
@@ -188,6 +190,7 @@ mod tests {
         assert_eq!(fields["alliance"]["abbreviation"], json!("SO4L"));
         assert!(fields["alliance"]["name"].is_string());
         assert!(fields["alliance_building_id"].is_null());
+        assert!(fields["alliance_building"].is_null());
         assert_eq!(fields["tracking_key"], json!("110176153_1755294119_116_15"));
         assert_eq!(
             fields["castle"],
@@ -201,6 +204,7 @@ mod tests {
         assert_eq!(fields["camp_id"], json!(null));
         assert!(fields["rally"].is_null());
         assert!(fields["structure_id"].is_null());
+        assert!(fields["structure"].is_null());
         let participants = fields["participants"]
             .as_array()
             .expect("participants array");
@@ -236,6 +240,7 @@ mod tests {
         assert_eq!(fields["commanders"]["secondary"]["awakened"], json!(false));
         assert_eq!(fields["commanders"]["secondary"]["star_level"], json!(4));
         assert_eq!(fields["app_id"], json!(2104267));
+        assert_eq!(fields["app_client"], json!("international"));
         assert_eq!(fields["app_uid"], json!(88504567));
         assert_eq!(
             fields["avatar_url"],
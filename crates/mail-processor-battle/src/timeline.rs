@@ -1,5 +1,7 @@
 //! Timeline extractor for Battle mail.
 
+use std::collections::BTreeMap;
+
 use mail_processor_sdk::{ExtractError, Extractor, Section, indexed_array_values, require_u64};
 use serde_json::{Map, Value, json};
 
@@ -39,58 +41,12 @@ impl Extractor for TimelineExtractor {
             entries.push(json!({ "tick": tick, "count": count }));
         }
 
-        // Event type (Et) mappings:
-        // - 18: reinforcements join
-        // - 26: reinforcements leave (Cnt may be omitted when march count hits 0)
-        // Some reports omit events entirely; treat missing or null as empty.
-        let events = match content.get("Events") {
-            None | Some(Value::Null) => Vec::new(),
-            Some(value) => indexed_array_values(value, "Events")?,
-        };
-        let mut event_entries = Vec::with_capacity(events.len());
-        for event in events {
-            let event_map = event.as_object().ok_or(ExtractError::InvalidFieldType {
-                field: "Events",
-                expected: "object",
-            })?;
-            let tick = require_u64(event, "T")?;
-            let event_type = require_u64(event, "Et")?;
-            let assist_units = match event_map.get("AssistUnits") {
-                Some(Value::Object(map)) => map,
-                Some(_) => {
-                    return Err(ExtractError::InvalidFieldType {
-                        field: "AssistUnits",
-                        expected: "object",
-                    });
-                }
-                None => {
-                    continue;
-                }
-            };
-            let player_id = require_signed_id_field(assist_units, "PId")?;
-            let player_name = require_string_field(assist_units, "PName")?;
-            let count = optional_u64_field(assist_units, "Cnt")?;
-            let event_id = optional_u64_field(assist_units, "TId")?;
-            let (avatar_url, frame_url) = parse_avatar(assist_units)?;
-            let primary_id = optional_u64_field(assist_units, "HId")?;
-            let primary_level = optional_u64_field(assist_units, "HLv")?;
-            let secondary_id = optional_u64_field(assist_units, "HId2")?;
-            let secondary_level = optional_u64_field(assist_units, "HLv2")?;
-            event_entries.push(json!({
-                "tick": tick,
-                "type": event_type,
-                "event_id": event_id,
-                "player_id": player_id,
-                "player_name": player_name,
-                "count": count.map(Value::from).unwrap_or(Value::Null),
-                "avatar_url": avatar_url,
-                "frame_url": frame_url,
-                "commanders": {
-                    "primary": { "id": primary_id, "level": primary_level },
-                    "secondary": { "id": secondary_id, "level": secondary_level },
-                },
-            }));
+        let events = parse_events(content)?;
+        let mut event_counts = BTreeMap::<&str, u64>::new();
+        for entry in &events {
+            *event_counts.entry(entry.event.kind()).or_default() += 1;
         }
+        let event_entries = events.iter().map(TimelineEntry::to_json).collect();
 
         let mut section = Section::new();
         section.insert("start_timestamp", Value::from(start_timestamp));
@@ -98,10 +54,215 @@ impl Extractor for TimelineExtractor {
         section.insert("start_tick", Value::from(start_tick));
         section.insert("sampling", Value::Array(entries));
         section.insert("events", Value::Array(event_entries));
+        section.insert("event_counts", json!(event_counts));
         Ok(section)
     }
 }
 
+/// Event type (`Et`) for a castle heal during the battle.
+const EVENT_CASTLE_HEALING: u64 = 16;
+/// Event type (`Et`) for reinforcements joining.
+const EVENT_REINFORCEMENT_JOIN: u64 = 18;
+/// Event type (`Et`) for reinforcements leaving. `Cnt` may be omitted when
+/// the march count hits 0.
+const EVENT_REINFORCEMENT_LEAVE: u64 = 26;
+
+/// A decoded timeline event with the tick it happened on.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct TimelineEntry {
+    pub(crate) tick: u64,
+    pub(crate) event: TimelineEvent,
+}
+
+/// Timeline event decoded by its `Et` type.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum TimelineEvent {
+    CastleHealing {
+        count: Option<u64>,
+        is_self: Option<bool>,
+    },
+    ReinforcementJoin(AssistUnits),
+    ReinforcementLeave(AssistUnits),
+    /// Unrecognized types, or known types missing their payload, kept as-is.
+    Raw {
+        event_type: u64,
+        payload: Map<String, Value>,
+    },
+}
+
+/// The `AssistUnits` payload of a reinforcement event.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct AssistUnits {
+    pub(crate) player_id: i64,
+    pub(crate) player_name: String,
+    pub(crate) count: Option<u64>,
+    pub(crate) event_id: Option<u64>,
+    pub(crate) is_self: Option<bool>,
+    pub(crate) avatar_url: Value,
+    pub(crate) frame_url: Value,
+    pub(crate) primary: (Option<u64>, Option<u64>),
+    pub(crate) secondary: (Option<u64>, Option<u64>),
+}
+
+impl TimelineEvent {
+    pub(crate) fn event_type(&self) -> u64 {
+        match self {
+            TimelineEvent::CastleHealing { .. } => EVENT_CASTLE_HEALING,
+            TimelineEvent::ReinforcementJoin(_) => EVENT_REINFORCEMENT_JOIN,
+            TimelineEvent::ReinforcementLeave(_) => EVENT_REINFORCEMENT_LEAVE,
+            TimelineEvent::Raw { event_type, .. } => *event_type,
+        }
+    }
+
+    pub(crate) fn kind(&self) -> &'static str {
+        match self {
+            TimelineEvent::CastleHealing { .. } => "castle_healing",
+            TimelineEvent::ReinforcementJoin(_) => "reinforcement_join",
+            TimelineEvent::ReinforcementLeave(_) => "reinforcement_leave",
+            TimelineEvent::Raw { .. } => "raw",
+        }
+    }
+
+    /// Troop count carried by the event, when it has one.
+    pub(crate) fn count(&self) -> Option<u64> {
+        match self {
+            TimelineEvent::CastleHealing { count, .. } => *count,
+            TimelineEvent::ReinforcementJoin(units) | TimelineEvent::ReinforcementLeave(units) => {
+                units.count
+            }
+            TimelineEvent::Raw { payload, .. } => payload
+                .values()
+                .find_map(|value| value.get("Cnt")?.as_u64()),
+        }
+    }
+}
+
+impl TimelineEntry {
+    fn to_json(&self) -> Value {
+        let mut fields = match &self.event {
+            TimelineEvent::CastleHealing { count, is_self } => json!({
+                "count": count,
+                "is_self": is_self,
+            }),
+            TimelineEvent::ReinforcementJoin(units) | TimelineEvent::ReinforcementLeave(units) => {
+                json!({
+                    "event_id": units.event_id,
+                    "player_id": units.player_id,
+                    "player_name": units.player_name,
+                    "count": units.count,
+                    "is_self": units.is_self,
+                    "avatar_url": units.avatar_url,
+                    "frame_url": units.frame_url,
+                    "commanders": {
+                        "primary": { "id": units.primary.0, "level": units.primary.1 },
+                        "secondary": { "id": units.secondary.0, "level": units.secondary.1 },
+                    },
+                })
+            }
+            TimelineEvent::Raw { payload, .. } => json!({
+                "count": self.event.count(),
+                "raw": payload,
+            }),
+        };
+        if let Value::Object(map) = &mut fields {
+            map.insert("tick".to_string(), Value::from(self.tick));
+            map.insert("type".to_string(), Value::from(self.event.event_type()));
+            map.insert("kind".to_string(), Value::from(self.event.kind()));
+        }
+        fields
+    }
+}
+
+/// Decode the content `Events` array. Missing or null events are empty.
+pub(crate) fn parse_events(
+    content: &Map<String, Value>,
+) -> Result<Vec<TimelineEntry>, ExtractError> {
+    let events = match content.get("Events") {
+        None | Some(Value::Null) => return Ok(Vec::new()),
+        Some(value) => indexed_array_values(value, "Events")?,
+    };
+
+    let mut entries = Vec::with_capacity(events.len());
+    for event in events {
+        let event_map = event.as_object().ok_or(ExtractError::InvalidFieldType {
+            field: "Events",
+            expected: "object",
+        })?;
+        let tick = require_u64(event, "T")?;
+        let event_type = require_u64(event, "Et")?;
+        entries.push(TimelineEntry {
+            tick,
+            event: parse_event(event_type, event_map)?,
+        });
+    }
+    Ok(entries)
+}
+
+fn parse_event(event_type: u64, event: &Map<String, Value>) -> Result<TimelineEvent, ExtractError> {
+    let payload = |field| match event.get(field) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::Object(map)) => Ok(Some(map)),
+        Some(_) => Err(ExtractError::InvalidFieldType {
+            field,
+            expected: "object",
+        }),
+    };
+
+    let decoded = match event_type {
+        EVENT_CASTLE_HEALING => payload("CastleHealing")?.map(|healing| {
+            Ok(TimelineEvent::CastleHealing {
+                count: optional_u64_field(healing, "Cnt")?,
+                is_self: optional_flag_field(healing, "IsSelf"),
+            })
+        }),
+        EVENT_REINFORCEMENT_JOIN => payload("AssistUnits")?
+            .map(|units| parse_assist_units(units).map(TimelineEvent::ReinforcementJoin)),
+        EVENT_REINFORCEMENT_LEAVE => payload("AssistUnits")?
+            .map(|units| parse_assist_units(units).map(TimelineEvent::ReinforcementLeave)),
+        _ => None,
+    };
+
+    decoded.unwrap_or_else(|| {
+        Ok(TimelineEvent::Raw {
+            event_type,
+            payload: event
+                .iter()
+                .filter(|(key, _)| !matches!(key.as_str(), "T" | "Et"))
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect(),
+        })
+    })
+}
+
+fn parse_assist_units(units: &Map<String, Value>) -> Result<AssistUnits, ExtractError> {
+    let (avatar_url, frame_url) = parse_avatar(units)?;
+    Ok(AssistUnits {
+        player_id: require_signed_id_field(units, "PId")?,
+        player_name: require_string_field(units, "PName")?,
+        count: optional_u64_field(units, "Cnt")?,
+        event_id: optional_u64_field(units, "TId")?,
+        is_self: optional_flag_field(units, "IsSelf"),
+        avatar_url,
+        frame_url,
+        primary: (
+            optional_u64_field(units, "HId")?,
+            optional_u64_field(units, "HLv")?,
+        ),
+        secondary: (
+            optional_u64_field(units, "HId2")?,
+            optional_u64_field(units, "HLv2")?,
+        ),
+    })
+}
+
+/// Read a flag stored as a boolean or a 0/1 number.
+fn optional_flag_field(object: &Map<String, Value>, field: &str) -> Option<bool> {
+    match object.get(field)? {
+        Value::Bool(flag) => Some(*flag),
+        value => value.as_u64().map(|flag| flag != 0),
+    }
+}
+
 /// Require a numeric identifier that can be either signed or unsigned.
 fn require_signed_id_field(
     object: &Map<String, Value>,
@@ -222,10 +383,12 @@ mod tests {
             json!({
                 "tick": 7,
                 "type": 18,
+                "kind": "reinforcement_join",
                 "event_id": 1234,
                 "player_id": 42,
                 "player_name": "Alpha",
                 "count": 99,
+                "is_self": Value::Null,
                 "avatar_url": Value::Null,
                 "frame_url": Value::Null,
                 "commanders": {
@@ -274,7 +437,7 @@ mod tests {
     }
 
     #[test]
-    fn timeline_extractor_skips_events_without_assist_units() {
+    fn timeline_extractor_keeps_events_without_assist_units() {
         let input = json!({
             "body": {
                 "content": {
@@ -286,22 +449,34 @@ mod tests {
                         1,
                         {
                             "T": 7,
-                            "Et": 18,
+                            "Et": 16,
                             "CastleHealing": { "Cnt": 5, "IsSelf": 1 }
                         },
                         2,
+                        {
+                            "T": 8,
+                            "Et": 18
+                        },
+                        3,
                         {
                             "T": 9,
-                            "Et": 18,
+                            "Et": 26,
                             "AssistUnits": {
                                 "PId": 42,
                                 "PName": "Alpha",
+                                "IsSelf": false,
                                 "Avatar": "null",
                                 "HId": 10,
                                 "HLv": 20,
                                 "HId2": 11,
                                 "HLv2": 21
                             }
+                        },
+                        4,
+                        {
+                            "T": 11,
+                            "Et": 99,
+                            "Mystery": { "Cnt": 3, "Flag": true }
                         }
                     ]
                 }
@@ -312,8 +487,61 @@ mod tests {
 
         let fields = section.fields();
         let events = fields["events"].as_array().expect("events array");
-        assert_eq!(events.len(), 1);
-        assert_eq!(events[0]["tick"], json!(9));
+        assert_eq!(events.len(), 4);
+        assert_eq!(
+            events[0],
+            json!({
+                "tick": 7,
+                "type": 16,
+                "kind": "castle_healing",
+                "count": 5,
+                "is_self": true
+            })
+        );
+        assert_eq!(
+            events[1],
+            json!({ "tick": 8, "type": 18, "kind": "raw", "count": null, "raw": {} })
+        );
+        assert_eq!(events[2]["kind"], json!("reinforcement_leave"));
+        assert_eq!(events[2]["is_self"], json!(false));
+        assert!(events[2]["count"].is_null());
+        assert_eq!(
+            events[3],
+            json!({
+                "tick": 11,
+                "type": 99,
+                "kind": "raw",
+                "count": 3,
+                "raw": { "Mystery": { "Cnt": 3, "Flag": true } }
+            })
+        );
+        assert_eq!(
+            fields["event_counts"],
+            json!({ "castle_healing": 1, "raw": 2, "reinforcement_leave": 1 })
+        );
+    }
+
+    #[test]
+    fn timeline_extractor_rejects_malformed_assist_units() {
+        let input = json!({
+            "body": {
+                "content": {
+                    "Bts": 10,
+                    "Ets": 20,
+                    "Btk": 5,
+                    "Samples": [],
+                    "Events": [1, { "T": 7, "Et": 18, "AssistUnits": 5 }]
+                }
+            }
+        });
+        let err = TimelineExtractor::new().extract(&input).unwrap_err();
+        assert!(matches!(
+            err,
+            ExtractError::InvalidFieldType {
+                field: "AssistUnits",
+                ..
+            }
+        ));
     }
 
     #[test]
@@ -334,6 +562,7 @@ mod tests {
         let fields = section.fields();
         let events = fields["events"].as_array().expect("events array");
         assert!(events.is_empty());
+        assert_eq!(fields["event_counts"], json!({}));
     }
 
     #[test]
@@ -9,7 +9,7 @@ mod opponent;
 mod player;
 mod sender;
 
-use mail_processor_sdk::{ProcessError, ProcessedMail, Processor};
+use mail_processor_sdk::{ProcessError, ProcessStats, ProcessedMail, Processor};
 use serde_json::Value;
 
 pub use mail_processor_sdk::{ExtractError, Section};
@@ -19,6 +19,13 @@ pub fn process_parallel(input: &Value) -> Result<ProcessedMail, ProcessError> {
     processor().process_parallel(input)
 }
 
+/// Process a decoded DuelBattle2 mail with parallel extractors and record per-section stats.
+pub fn process_parallel_with_stats(
+    input: &Value,
+) -> (Result<ProcessedMail, ProcessError>, ProcessStats) {
+    processor().process_parallel_with_stats(input)
+}
+
 /// Process a decoded DuelBattle2 mail in extractor order.
 pub fn process_sequential(input: &Value) -> Result<ProcessedMail, ProcessError> {
     processor().process_sequential(input)
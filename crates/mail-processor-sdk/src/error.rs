@@ -20,6 +20,8 @@ pub enum ExtractError {
         /// The expected JSON type.
         expected: &'static str,
     },
+    /// The extractor misused its output section.
+    InvalidSection(SectionError),
 }
 
 impl fmt::Display for ExtractError {
@@ -30,11 +32,56 @@ impl fmt::Display for ExtractError {
             ExtractError::InvalidFieldType { field, expected } => {
                 write!(f, "invalid type for {field}; expected {expected}")
             }
+            ExtractError::InvalidSection(source) => write!(f, "invalid section: {source}"),
         }
     }
 }
 
-impl Error for ExtractError {}
+impl Error for ExtractError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ExtractError::InvalidSection(source) => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<SectionError> for ExtractError {
+    fn from(source: SectionError) -> Self {
+        ExtractError::InvalidSection(source)
+    }
+}
+
+/// Errors raised when a section is used with the wrong shape.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SectionError {
+    /// An object operation was attempted on an array-backed section.
+    NotObject,
+    /// An array operation was attempted on an object-backed section.
+    NotArray,
+    /// A nested insert was given an empty path.
+    EmptyPath,
+    /// A nested insert walked through a value that is not an object.
+    NotNestedObject {
+        /// The path segment holding the non-object value.
+        key: String,
+    },
+}
+
+impl fmt::Display for SectionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SectionError::NotObject => write!(f, "section is backed by an array"),
+            SectionError::NotArray => write!(f, "section is backed by object fields"),
+            SectionError::EmptyPath => write!(f, "nested insert requires at least one key"),
+            SectionError::NotNestedObject { key } => {
+                write!(f, "nested value at {key} is not an object")
+            }
+        }
+    }
+}
+
+impl Error for SectionError {}
 
 /// Errors raised when running a processor across multiple extractors.
 #[derive(Debug)]
@@ -58,23 +58,16 @@ fn is_indexed_array(array: &[Value]) -> bool {
         return false;
     }
 
-    let mut expected = match array.first().and_then(Value::as_u64) {
+    let start = match array.first().and_then(Value::as_u64) {
         Some(value) if value == 0 || value == 1 => value,
         _ => return false,
     };
 
-    for value in array.iter().step_by(2) {
-        let index = match value.as_u64() {
-            Some(index) => index,
-            None => return false,
-        };
-        if index != expected {
-            return false;
-        }
-        expected += 1;
-    }
-
-    true
+    array
+        .iter()
+        .step_by(2)
+        .zip(start..)
+        .all(|(value, expected)| value.as_u64() == Some(expected))
 }
 
 #[cfg(test)]
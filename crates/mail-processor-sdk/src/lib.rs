@@ -7,10 +7,14 @@
 
 mod error;
 mod extract;
+mod parallel;
 mod processor;
+mod stats;
 mod types;
 
-pub use error::{ExtractError, ProcessError};
+pub use error::{ExtractError, ProcessError, SectionError};
 pub use extract::{indexed_array_values, require_object, require_string, require_u64};
+pub use parallel::Parallelism;
 pub use processor::{Extractor, Processor};
-pub use types::{ProcessedMail, Section};
+pub use stats::{ProcessStats, SectionOutcome, SectionStats};
+pub use types::{ProcessedMail, Section, SectionView};
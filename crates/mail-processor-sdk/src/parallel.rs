@@ -0,0 +1,154 @@
+//! Bounded parallelism for work inside a single extractor.
+
+use std::num::NonZeroUsize;
+use std::panic;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+/// Upper bound on worker threads an extractor may use for its own items.
+///
+/// Extractors already run on their own thread per section, and processors are
+/// often driven concurrently, so per-item work shares a small pool instead of
+/// spawning a thread per item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Parallelism {
+    max_workers: NonZeroUsize,
+}
+
+impl Parallelism {
+    /// Allow up to `max_workers` threads; zero is treated as one.
+    pub fn new(max_workers: usize) -> Self {
+        Self {
+            max_workers: NonZeroUsize::new(max_workers).unwrap_or(NonZeroUsize::MIN),
+        }
+    }
+
+    /// Run every item on the calling thread.
+    pub fn sequential() -> Self {
+        Self::new(1)
+    }
+
+    /// Use as many workers as the machine reports available parallelism.
+    pub fn available() -> Self {
+        Self {
+            max_workers: thread::available_parallelism().unwrap_or(NonZeroUsize::MIN),
+        }
+    }
+
+    /// The maximum number of worker threads.
+    pub fn max_workers(&self) -> usize {
+        self.max_workers.get()
+    }
+
+    /// Apply `f` to every item and return the results in input order.
+    ///
+    /// Workers pull the next unclaimed item from a shared counter, so uneven
+    /// items still balance across the pool. A panic in `f` is resumed on the
+    /// calling thread after all workers stop.
+    pub fn map<T, R, F>(&self, items: &[T], f: F) -> Vec<R>
+    where
+        T: Sync,
+        R: Send,
+        F: Fn(&T) -> R + Sync,
+    {
+        let workers = self.max_workers().min(items.len());
+        if workers <= 1 {
+            return items.iter().map(f).collect();
+        }
+
+        let next = AtomicUsize::new(0);
+        let worker = || {
+            let mut results = Vec::new();
+            loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(item) = items.get(index) else {
+                    return results;
+                };
+                results.push((index, f(item)));
+            }
+        };
+
+        let mut indexed = Vec::with_capacity(items.len());
+        let mut panicked = None;
+        thread::scope(|scope| {
+            let handles = (0..workers)
+                .map(|_| scope.spawn(worker))
+                .collect::<Vec<_>>();
+            for handle in handles {
+                match handle.join() {
+                    Ok(results) => indexed.extend(results),
+                    Err(payload) => panicked = Some(payload),
+                }
+            }
+        });
+        if let Some(payload) = panicked {
+            panic::resume_unwind(payload);
+        }
+
+        indexed.sort_unstable_by_key(|(index, _)| *index);
+        indexed.into_iter().map(|(_, result)| result).collect()
+    }
+}
+
+impl Default for Parallelism {
+    fn default() -> Self {
+        Self::available()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+    use std::sync::Mutex;
+
+    #[test]
+    fn new_treats_zero_as_one_worker() {
+        assert_eq!(Parallelism::new(0).max_workers(), 1);
+        assert_eq!(Parallelism::sequential().max_workers(), 1);
+        assert!(Parallelism::available().max_workers() >= 1);
+    }
+
+    #[test]
+    fn map_preserves_input_order() {
+        let items = (0..257).collect::<Vec<u64>>();
+        let doubled = Parallelism::new(4).map(&items, |item| item * 2);
+        assert_eq!(
+            doubled,
+            items.iter().map(|item| item * 2).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn map_bounds_worker_threads() {
+        let items = (0..64).collect::<Vec<u64>>();
+        let threads = Mutex::new(HashSet::new());
+        Parallelism::new(3).map(&items, |_| {
+            threads
+                .lock()
+                .expect("thread set lock")
+                .insert(thread::current().id());
+        });
+        assert!(threads.into_inner().expect("thread set").len() <= 3);
+    }
+
+    #[test]
+    fn map_sequential_stays_on_calling_thread() {
+        let caller = thread::current().id();
+        let items = [1, 2, 3];
+        let ids = Parallelism::sequential().map(&items, |_| thread::current().id());
+        assert!(ids.into_iter().all(|id| id == caller));
+    }
+
+    #[test]
+    fn map_resumes_worker_panics() {
+        let items = (0..8).collect::<Vec<u64>>();
+        let result = panic::catch_unwind(|| {
+            Parallelism::new(2).map(&items, |item| {
+                assert_ne!(*item, 5, "boom");
+                *item
+            })
+        });
+        assert!(result.is_err());
+    }
+}
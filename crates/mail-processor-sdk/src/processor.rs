@@ -1,10 +1,13 @@
 //! Extractor trait and processor orchestration.
 
 use std::collections::HashSet;
+use std::time::{Duration, Instant};
 
 use serde_json::Value;
 
-use crate::{ExtractError, ProcessError, ProcessedMail, Section};
+use crate::{
+    ExtractError, ProcessError, ProcessStats, ProcessedMail, Section, SectionOutcome, SectionStats,
+};
 
 /// Extracts a section of processed data from a decoded mail JSON object.
 pub trait Extractor: Send + Sync {
@@ -28,13 +31,60 @@ impl Processor {
 
     /// Run extractors sequentially in the order provided.
     pub fn process_sequential(&self, input: &Value) -> Result<ProcessedMail, ProcessError> {
+        self.run_sequential(input, None)
+    }
+
+    /// Run extractors sequentially and record per-section timing and sizes.
+    ///
+    /// Stats are returned even when processing fails so slow or failing
+    /// extractors can still be reported.
+    pub fn process_sequential_with_stats(
+        &self,
+        input: &Value,
+    ) -> (Result<ProcessedMail, ProcessError>, ProcessStats) {
+        let started = Instant::now();
+        let mut stats = ProcessStats::new(input);
+        let result = self.run_sequential(input, Some(&mut stats));
+        stats.finish(started.elapsed());
+        (result, stats)
+    }
+
+    /// Run extractors in parallel without assuming dependencies between them.
+    pub fn process_parallel(&self, input: &Value) -> Result<ProcessedMail, ProcessError> {
+        self.run_parallel(input, None)
+    }
+
+    /// Run extractors in parallel and record per-section timing and sizes.
+    ///
+    /// Stats are returned even when processing fails so slow or failing
+    /// extractors can still be reported.
+    pub fn process_parallel_with_stats(
+        &self,
+        input: &Value,
+    ) -> (Result<ProcessedMail, ProcessError>, ProcessStats) {
+        let started = Instant::now();
+        let mut stats = ProcessStats::new(input);
+        let result = self.run_parallel(input, Some(&mut stats));
+        stats.finish(started.elapsed());
+        (result, stats)
+    }
+
+    fn run_sequential(
+        &self,
+        input: &Value,
+        mut stats: Option<&mut ProcessStats>,
+    ) -> Result<ProcessedMail, ProcessError> {
         self.ensure_unique_sections()?;
         let mut processed = ProcessedMail::new();
         for extractor in &self.extractors {
             let section = extractor.section();
-            let data = extractor
-                .extract(input)
-                .map_err(|source| ProcessError::ExtractorFailed { section, source })?;
+            let started = Instant::now();
+            let result = extractor.extract(input);
+            if let Some(stats) = stats.as_deref_mut() {
+                stats.push(section_stats(section, started.elapsed(), Some(&result)));
+            }
+            let data =
+                result.map_err(|source| ProcessError::ExtractorFailed { section, source })?;
             if processed.insert(section.to_string(), data).is_some() {
                 return Err(ProcessError::DuplicateSection { section });
             }
@@ -42,33 +92,53 @@ impl Processor {
         Ok(processed)
     }
 
-    /// Run extractors in parallel without assuming dependencies between them.
-    pub fn process_parallel(&self, input: &Value) -> Result<ProcessedMail, ProcessError> {
+    fn run_parallel(
+        &self,
+        input: &Value,
+        stats: Option<&mut ProcessStats>,
+    ) -> Result<ProcessedMail, ProcessError> {
         self.ensure_unique_sections()?;
-        let mut results = Vec::with_capacity(self.extractors.len());
 
-        std::thread::scope(|scope| {
+        let results = std::thread::scope(|scope| {
             let mut handles = Vec::with_capacity(self.extractors.len());
             for extractor in &self.extractors {
                 let extractor = extractor.as_ref();
                 let section = extractor.section();
+                let started = Instant::now();
                 // Spawn each extractor so independent sections can run concurrently.
-                let handle = scope.spawn(move || extractor.extract(input));
-                handles.push((section, handle));
+                let handle = scope.spawn(move || {
+                    let result = extractor.extract(input);
+                    (result, started.elapsed())
+                });
+                handles.push((section, started, handle));
             }
 
-            for (section, handle) in handles {
-                let result = handle
-                    .join()
-                    .map_err(|_| ProcessError::ExtractorPanicked { section })?;
-                results.push((section, result));
+            // Join every handle so stats cover all sections, even after a panic.
+            let mut results = Vec::with_capacity(handles.len());
+            for (section, started, handle) in handles {
+                match handle.join() {
+                    Ok((result, elapsed)) => results.push((section, Some(result), elapsed)),
+                    Err(_) => results.push((section, None, started.elapsed())),
+                }
             }
+            results
+        });
 
-            Ok(())
-        })?;
+        if let Some(stats) = stats {
+            for (section, result, elapsed) in &results {
+                stats.push(section_stats(section, *elapsed, result.as_ref()));
+            }
+        }
+
+        if let Some((section, _, _)) = results.iter().find(|(_, result, _)| result.is_none()) {
+            return Err(ProcessError::ExtractorPanicked { section });
+        }
 
         let mut processed = ProcessedMail::new();
-        for (section, result) in results {
+        for (section, result, _) in results {
+            let Some(result) = result else {
+                continue;
+            };
             let data =
                 result.map_err(|source| ProcessError::ExtractorFailed { section, source })?;
             if processed.insert(section.to_string(), data).is_some() {
@@ -91,6 +161,23 @@ impl Processor {
     }
 }
 
+fn section_stats(
+    section: &'static str,
+    elapsed: Duration,
+    result: Option<&Result<Section, ExtractError>>,
+) -> SectionStats {
+    match result {
+        Some(Ok(data)) => SectionStats::new(
+            section,
+            elapsed,
+            Some(data.len()),
+            SectionOutcome::Succeeded,
+        ),
+        Some(Err(_)) => SectionStats::new(section, elapsed, None, SectionOutcome::Failed),
+        None => SectionStats::new(section, elapsed, None, SectionOutcome::Panicked),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -140,6 +227,44 @@ mod tests {
         assert_eq!(section.fields().get("value").unwrap(), &json!(20));
     }
 
+    #[test]
+    fn process_parallel_with_stats_records_sections() {
+        let processor = Processor::new(vec![
+            Box::new(TestExtractor {
+                section_name: "one",
+            }),
+            Box::new(TestExtractor {
+                section_name: "two",
+            }),
+        ]);
+        let input = json!({"value": 40});
+        let (result, stats) = processor.process_parallel_with_stats(&input);
+        assert!(result.is_ok());
+        assert_eq!(stats.input_len(), 1);
+        assert_eq!(stats.input_bytes(), None);
+        let sections: Vec<_> = stats.sections().iter().map(|s| s.section()).collect();
+        assert_eq!(sections, vec!["one", "two"]);
+        assert!(
+            stats
+                .sections()
+                .iter()
+                .all(|s| s.outcome() == SectionOutcome::Succeeded && s.output_len() == Some(1))
+        );
+    }
+
+    #[test]
+    fn process_sequential_with_stats_records_failures() {
+        let processor = Processor::new(vec![Box::new(TestExtractor {
+            section_name: "one",
+        })]);
+        let input = json!({"other": 1});
+        let (result, stats) = processor.process_sequential_with_stats(&input);
+        assert!(matches!(result, Err(ProcessError::ExtractorFailed { .. })));
+        assert_eq!(stats.sections().len(), 1);
+        assert_eq!(stats.sections()[0].outcome(), SectionOutcome::Failed);
+        assert_eq!(stats.sections()[0].output_len(), None);
+    }
+
     #[test]
     fn process_rejects_duplicate_sections() {
         let processor = Processor::new(vec![
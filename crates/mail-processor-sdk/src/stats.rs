@@ -0,0 +1,173 @@
+//! Timing and size counters recorded while running extractors.
+
+use std::time::Duration;
+
+use serde_json::Value;
+
+/// How an extractor finished.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SectionOutcome {
+    /// The extractor returned a section.
+    Succeeded,
+    /// The extractor returned an error.
+    Failed,
+    /// The extractor panicked while running in parallel.
+    Panicked,
+}
+
+impl SectionOutcome {
+    /// Return a stable label for logs and metrics.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SectionOutcome::Succeeded => "succeeded",
+            SectionOutcome::Failed => "failed",
+            SectionOutcome::Panicked => "panicked",
+        }
+    }
+}
+
+/// Counters recorded for a single extractor run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SectionStats {
+    section: &'static str,
+    elapsed: Duration,
+    output_len: Option<usize>,
+    outcome: SectionOutcome,
+}
+
+impl SectionStats {
+    pub(crate) fn new(
+        section: &'static str,
+        elapsed: Duration,
+        output_len: Option<usize>,
+        outcome: SectionOutcome,
+    ) -> Self {
+        Self {
+            section,
+            elapsed,
+            output_len,
+            outcome,
+        }
+    }
+
+    /// The section name written by the extractor.
+    pub fn section(&self) -> &'static str {
+        self.section
+    }
+
+    /// Wall time spent inside the extractor.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Number of fields or array entries in the extracted section, if it succeeded.
+    pub fn output_len(&self) -> Option<usize> {
+        self.output_len
+    }
+
+    /// How the extractor finished.
+    pub fn outcome(&self) -> SectionOutcome {
+        self.outcome
+    }
+}
+
+/// Counters recorded for a full processor run.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProcessStats {
+    input_len: usize,
+    input_bytes: Option<usize>,
+    elapsed: Duration,
+    sections: Vec<SectionStats>,
+}
+
+impl ProcessStats {
+    pub(crate) fn new(input: &Value) -> Self {
+        Self {
+            input_len: top_level_len(input),
+            ..Self::default()
+        }
+    }
+
+    pub(crate) fn push(&mut self, stats: SectionStats) {
+        self.sections.push(stats);
+    }
+
+    pub(crate) fn finish(&mut self, elapsed: Duration) {
+        self.elapsed = elapsed;
+    }
+
+    /// Record the size of the mail as received, such as its stored byte length.
+    ///
+    /// The processor only sees decoded JSON, so callers that hold the raw
+    /// payload supply its size.
+    pub fn set_input_bytes(&mut self, bytes: usize) {
+        self.input_bytes = Some(bytes);
+    }
+
+    /// Number of top-level fields or array entries in the decoded input.
+    pub fn input_len(&self) -> usize {
+        self.input_len
+    }
+
+    /// Size of the mail as received, when the caller recorded it.
+    pub fn input_bytes(&self) -> Option<usize> {
+        self.input_bytes
+    }
+
+    /// Wall time for the whole processor run.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Per-extractor counters in extractor order.
+    pub fn sections(&self) -> &[SectionStats] {
+        &self.sections
+    }
+
+    /// Return the slowest extractor, if any ran.
+    pub fn slowest(&self) -> Option<&SectionStats> {
+        self.sections.iter().max_by_key(|stats| stats.elapsed)
+    }
+}
+
+fn top_level_len(value: &Value) -> usize {
+    match value {
+        Value::Object(fields) => fields.len(),
+        Value::Array(items) => items.len(),
+        _ => 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn input_sizes_count_top_level_entries_and_caller_bytes() {
+        let mut stats = ProcessStats::new(&json!({ "id": "mail-1", "body": { "a": 1 } }));
+        assert_eq!(stats.input_len(), 2);
+        assert_eq!(stats.input_bytes(), None);
+        stats.set_input_bytes(512);
+        assert_eq!(stats.input_bytes(), Some(512));
+        assert_eq!(ProcessStats::new(&json!([1, 2, 3])).input_len(), 3);
+    }
+
+    #[test]
+    fn slowest_returns_longest_section() {
+        let mut stats = ProcessStats::default();
+        stats.push(SectionStats::new(
+            "fast",
+            Duration::from_millis(1),
+            Some(1),
+            SectionOutcome::Succeeded,
+        ));
+        stats.push(SectionStats::new(
+            "slow",
+            Duration::from_millis(5),
+            None,
+            SectionOutcome::Failed,
+        ));
+        assert_eq!(stats.slowest().unwrap().section(), "slow");
+    }
+}
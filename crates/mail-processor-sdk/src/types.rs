@@ -3,7 +3,9 @@
 use std::collections::BTreeMap;
 
 use serde::Serialize;
-use serde_json::Value;
+use serde_json::{Map, Value};
+
+use crate::SectionError;
 
 /// A collection of extracted fields for a processor section.
 #[derive(Debug, Clone, PartialEq)]
@@ -36,22 +38,105 @@ impl Section {
     /// Insert a value into the section object.
     ///
     /// # Panics
-    /// Panics if the section is backed by an array.
+    /// Panics if the section is backed by an array. Use [`Section::try_insert`]
+    /// when the section shape is not known.
     pub fn insert(&mut self, key: impl Into<String>, value: Value) -> Option<Value> {
+        match self.try_insert(key, value) {
+            Ok(previous) => previous,
+            Err(_) => panic!("attempted to insert into an array section"),
+        }
+    }
+
+    /// Insert a value into the section object, failing for array-backed sections.
+    pub fn try_insert(
+        &mut self,
+        key: impl Into<String>,
+        value: Value,
+    ) -> Result<Option<Value>, SectionError> {
+        match &mut self.data {
+            SectionData::Object(fields) => Ok(fields.insert(key.into(), value)),
+            SectionData::Array(_) => Err(SectionError::NotObject),
+        }
+    }
+
+    /// Insert a value under a nested object path, creating missing objects.
+    ///
+    /// The first key addresses a section field; later keys address fields of
+    /// nested JSON objects. Returns the value previously stored at the path.
+    pub fn try_insert_nested(
+        &mut self,
+        path: &[&str],
+        value: Value,
+    ) -> Result<Option<Value>, SectionError> {
+        let SectionData::Object(fields) = &mut self.data else {
+            return Err(SectionError::NotObject);
+        };
+        let Some((last, parents)) = path.split_last() else {
+            return Err(SectionError::EmptyPath);
+        };
+        let Some((first, rest)) = parents.split_first() else {
+            return Ok(fields.insert((*last).to_string(), value));
+        };
+
+        let mut current = fields
+            .entry((*first).to_string())
+            .or_insert_with(|| Value::Object(Map::new()));
+        let mut current_key = *first;
+        for key in rest {
+            current = current
+                .as_object_mut()
+                .ok_or_else(|| SectionError::NotNestedObject {
+                    key: current_key.to_string(),
+                })?
+                .entry(*key)
+                .or_insert_with(|| Value::Object(Map::new()));
+            current_key = key;
+        }
+
+        let object = current
+            .as_object_mut()
+            .ok_or_else(|| SectionError::NotNestedObject {
+                key: current_key.to_string(),
+            })?;
+        Ok(object.insert((*last).to_string(), value))
+    }
+
+    /// Append a value to an array-backed section.
+    pub fn try_push(&mut self, value: Value) -> Result<(), SectionError> {
         match &mut self.data {
-            SectionData::Object(fields) => fields.insert(key.into(), value),
-            SectionData::Array(_) => panic!("attempted to insert into an array section"),
+            SectionData::Array(values) => {
+                values.push(value);
+                Ok(())
+            }
+            SectionData::Object(_) => Err(SectionError::NotArray),
         }
     }
 
     /// Read the extracted fields for an object-backed section.
     ///
     /// # Panics
-    /// Panics if the section is backed by an array.
+    /// Panics if the section is backed by an array. Use [`Section::try_fields`]
+    /// or [`Section::view`] when the section shape is not known.
     pub fn fields(&self) -> &BTreeMap<String, Value> {
+        match self.try_fields() {
+            Ok(fields) => fields,
+            Err(_) => panic!("attempted to read fields from an array section"),
+        }
+    }
+
+    /// Read the extracted fields, failing for array-backed sections.
+    pub fn try_fields(&self) -> Result<&BTreeMap<String, Value>, SectionError> {
         match &self.data {
-            SectionData::Object(fields) => fields,
-            SectionData::Array(_) => panic!("attempted to read fields from an array section"),
+            SectionData::Object(fields) => Ok(fields),
+            SectionData::Array(_) => Err(SectionError::NotObject),
+        }
+    }
+
+    /// Borrow the section payload as either object fields or array values.
+    pub fn view(&self) -> SectionView<'_> {
+        match &self.data {
+            SectionData::Object(fields) => SectionView::Object(fields),
+            SectionData::Array(values) => SectionView::Array(values.as_slice()),
         }
     }
 
@@ -62,6 +147,28 @@ impl Section {
             SectionData::Object(_) => None,
         }
     }
+
+    /// Return the number of object fields or array entries.
+    pub fn len(&self) -> usize {
+        match &self.data {
+            SectionData::Object(fields) => fields.len(),
+            SectionData::Array(values) => values.len(),
+        }
+    }
+
+    /// Return true when the section has no fields or entries.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// A borrowed view of a section payload.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SectionView<'a> {
+    /// Object fields keyed by name.
+    Object(&'a BTreeMap<String, Value>),
+    /// Array payload values.
+    Array(&'a [Value]),
 }
 
 impl Default for Section {
@@ -129,6 +236,58 @@ mod tests {
         assert_eq!(encoded, json!([{ "id": 1 }, { "id": 2 }]));
     }
 
+    #[test]
+    fn try_insert_rejects_array_section() {
+        let mut section = Section::from_array(vec![]);
+        let err = section.try_insert("mail_id", json!("mail-4")).unwrap_err();
+        assert_eq!(err, SectionError::NotObject);
+        assert_eq!(section.try_fields().unwrap_err(), SectionError::NotObject);
+    }
+
+    #[test]
+    fn try_push_appends_to_array_section() {
+        let mut section = Section::from_array(vec![json!(1)]);
+        section.try_push(json!(2)).unwrap();
+        assert_eq!(section.view(), SectionView::Array(&[json!(1), json!(2)]));
+        let mut object = Section::new();
+        assert_eq!(object.try_push(json!(3)), Err(SectionError::NotArray));
+    }
+
+    #[test]
+    fn try_insert_nested_creates_objects() {
+        let mut section = Section::new();
+        section
+            .try_insert_nested(&["sender", "commander", "id"], json!(12))
+            .unwrap();
+        section
+            .try_insert_nested(&["sender", "commander", "level"], json!(60))
+            .unwrap();
+        let encoded = serde_json::to_value(section).expect("serialize section");
+        assert_eq!(
+            encoded,
+            json!({ "sender": { "commander": { "id": 12, "level": 60 } } })
+        );
+    }
+
+    #[test]
+    fn try_insert_nested_rejects_non_object_parent() {
+        let mut section = Section::new();
+        section.insert("sender", json!(1));
+        let err = section
+            .try_insert_nested(&["sender", "id"], json!(2))
+            .unwrap_err();
+        assert_eq!(
+            err,
+            SectionError::NotNestedObject {
+                key: "sender".to_string()
+            }
+        );
+        assert_eq!(
+            section.try_insert_nested(&[], json!(3)),
+            Err(SectionError::EmptyPath)
+        );
+    }
+
     #[test]
     fn processed_mail_serializes_as_section_map() {
         let mut section = Section::new();
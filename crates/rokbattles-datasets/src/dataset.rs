@@ -0,0 +1,323 @@
+//! Dataset file parsing and id lookups.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+
+use crate::{
+    Armament, Commander, DatasetError, EquipmentItem, Formation, Inscription, LocalizedText,
+    LootItem,
+};
+
+const ARMAMENTS_YAML: &str = include_str!("../../../datasets/armaments.yaml");
+const COMMANDERS_YAML: &str = include_str!("../../../datasets/commanders.yaml");
+const EQUIPMENT_YAML: &str = include_str!("../../../datasets/equipment.yaml");
+const FORMATIONS_YAML: &str = include_str!("../../../datasets/formations.yaml");
+const INSCRIPTIONS_YAML: &str = include_str!("../../../datasets/inscriptions.yaml");
+const LOOT_YAML: &str = include_str!("../../../datasets/loot.yaml");
+
+/// Embedded dataset text, in [`Datasets::FILES`] order.
+pub(crate) const EMBEDDED: [&str; 6] = [
+    ARMAMENTS_YAML,
+    COMMANDERS_YAML,
+    EQUIPMENT_YAML,
+    FORMATIONS_YAML,
+    INSCRIPTIONS_YAML,
+    LOOT_YAML,
+];
+
+/// All datasets keyed by their in-game ids.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Datasets {
+    armaments: BTreeMap<u64, Armament>,
+    commanders: BTreeMap<u64, Commander>,
+    equipment_slots: BTreeMap<u64, LocalizedText>,
+    equipment_items: BTreeMap<u64, EquipmentItem>,
+    formations: BTreeMap<u64, Formation>,
+    inscriptions: BTreeMap<u64, Inscription>,
+    loot: BTreeMap<u64, BTreeMap<u64, LootItem>>,
+}
+
+/// Raw YAML text for every dataset file.
+struct Sources<'a> {
+    armaments: &'a str,
+    commanders: &'a str,
+    equipment: &'a str,
+    formations: &'a str,
+    inscriptions: &'a str,
+    loot: &'a str,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct ArmamentsFile {
+    pub(crate) armaments: BTreeMap<u64, Armament>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct CommandersFile {
+    pub(crate) commanders: BTreeMap<u64, Commander>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct EquipmentFile {
+    pub(crate) equipment: EquipmentSection,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct EquipmentSection {
+    pub(crate) slot: BTreeMap<u64, LocalizedText>,
+    pub(crate) item: BTreeMap<u64, EquipmentItem>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct FormationsFile {
+    pub(crate) formations: BTreeMap<u64, Formation>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct InscriptionsFile {
+    pub(crate) inscriptions: BTreeMap<u64, Inscription>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct LootFile {
+    pub(crate) loot: BTreeMap<u64, BTreeMap<u64, LootItem>>,
+}
+
+impl Datasets {
+    /// Dataset file names, as found in the repository `datasets/` directory.
+    pub const FILES: [&'static str; 6] = [
+        "armaments.yaml",
+        "commanders.yaml",
+        "equipment.yaml",
+        "formations.yaml",
+        "inscriptions.yaml",
+        "loot.yaml",
+    ];
+
+    /// Parse the datasets embedded at compile time.
+    pub fn embedded() -> Result<Self, DatasetError> {
+        let [
+            armaments,
+            commanders,
+            equipment,
+            formations,
+            inscriptions,
+            loot,
+        ] = EMBEDDED;
+        Self::parse_sources(&Sources {
+            armaments,
+            commanders,
+            equipment,
+            formations,
+            inscriptions,
+            loot,
+        })
+    }
+
+    /// Load datasets from a directory containing every file in [`Datasets::FILES`].
+    ///
+    /// Useful for testing local edits without rebuilding.
+    pub fn from_dir(dir: &Path) -> Result<Self, DatasetError> {
+        let [
+            armaments,
+            commanders,
+            equipment,
+            formations,
+            inscriptions,
+            loot,
+        ] = Self::FILES.map(|file| read_file(dir, file));
+        Self::parse_sources(&Sources {
+            armaments: &armaments?,
+            commanders: &commanders?,
+            equipment: &equipment?,
+            formations: &formations?,
+            inscriptions: &inscriptions?,
+            loot: &loot?,
+        })
+    }
+
+    fn parse_sources(sources: &Sources<'_>) -> Result<Self, DatasetError> {
+        let armaments: ArmamentsFile = parse("armaments.yaml", sources.armaments)?;
+        let commanders: CommandersFile = parse("commanders.yaml", sources.commanders)?;
+        let equipment: EquipmentFile = parse("equipment.yaml", sources.equipment)?;
+        let formations: FormationsFile = parse("formations.yaml", sources.formations)?;
+        let inscriptions: InscriptionsFile = parse("inscriptions.yaml", sources.inscriptions)?;
+        let loot: LootFile = parse("loot.yaml", sources.loot)?;
+
+        Ok(Self {
+            armaments: armaments.armaments,
+            commanders: commanders.commanders,
+            equipment_slots: equipment.equipment.slot,
+            equipment_items: equipment.equipment.item,
+            formations: formations.formations,
+            inscriptions: inscriptions.inscriptions,
+            loot: loot.loot,
+        })
+    }
+
+    /// Look up an armament stat (buff) by id.
+    pub fn armament(&self, id: u64) -> Option<&Armament> {
+        self.armaments.get(&id)
+    }
+
+    /// Look up a commander by id.
+    pub fn commander(&self, id: u64) -> Option<&Commander> {
+        self.commanders.get(&id)
+    }
+
+    /// Look up an equipment slot name by slot number.
+    pub fn equipment_slot(&self, slot: u64) -> Option<&LocalizedText> {
+        self.equipment_slots.get(&slot)
+    }
+
+    /// Look up an equipment item by id.
+    pub fn equipment_item(&self, id: u64) -> Option<&EquipmentItem> {
+        self.equipment_items.get(&id)
+    }
+
+    /// Look up a formation by id.
+    pub fn formation(&self, id: u64) -> Option<&Formation> {
+        self.formations.get(&id)
+    }
+
+    /// Look up an inscription by id.
+    pub fn inscription(&self, id: u64) -> Option<&Inscription> {
+        self.inscriptions.get(&id)
+    }
+
+    /// Look up a loot entry by NPC loot type and sub type.
+    pub fn loot(&self, loot_type: u64, sub_type: u64) -> Option<&LootItem> {
+        self.loot.get(&loot_type)?.get(&sub_type)
+    }
+
+    /// Iterate armament stats in id order.
+    pub fn armaments(&self) -> impl Iterator<Item = (u64, &Armament)> {
+        self.armaments.iter().map(|(id, entry)| (*id, entry))
+    }
+
+    /// Iterate commanders in id order.
+    pub fn commanders(&self) -> impl Iterator<Item = (u64, &Commander)> {
+        self.commanders.iter().map(|(id, entry)| (*id, entry))
+    }
+
+    /// Iterate equipment slot names in slot order.
+    pub fn equipment_slots(&self) -> impl Iterator<Item = (u64, &LocalizedText)> {
+        self.equipment_slots.iter().map(|(id, entry)| (*id, entry))
+    }
+
+    /// Iterate equipment items in id order.
+    pub fn equipment_items(&self) -> impl Iterator<Item = (u64, &EquipmentItem)> {
+        self.equipment_items.iter().map(|(id, entry)| (*id, entry))
+    }
+
+    /// Iterate formations in id order.
+    pub fn formations(&self) -> impl Iterator<Item = (u64, &Formation)> {
+        self.formations.iter().map(|(id, entry)| (*id, entry))
+    }
+
+    /// Iterate inscriptions in id order.
+    pub fn inscriptions(&self) -> impl Iterator<Item = (u64, &Inscription)> {
+        self.inscriptions.iter().map(|(id, entry)| (*id, entry))
+    }
+
+    /// Iterate loot entries as `(type, sub_type, entry)` in key order.
+    pub fn loot_items(&self) -> impl Iterator<Item = (u64, u64, &LootItem)> {
+        self.loot.iter().flat_map(|(loot_type, entries)| {
+            entries
+                .iter()
+                .map(move |(sub_type, entry)| (*loot_type, *sub_type, entry))
+        })
+    }
+}
+
+pub(crate) fn read_file(dir: &Path, file: &'static str) -> Result<String, DatasetError> {
+    let path = dir.join(file);
+    fs::read_to_string(&path).map_err(|source| DatasetError::Io { path, source })
+}
+
+pub(crate) fn parse<T: DeserializeOwned>(
+    file: &'static str,
+    text: &str,
+) -> Result<T, DatasetError> {
+    serde_yaml::from_str(text).map_err(|source| DatasetError::Parse { file, source })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CommanderRarity, CommanderSkill, InscriptionRarity, Locale};
+
+    #[test]
+    fn embedded_datasets_read_typed_fields() {
+        let datasets = Datasets::embedded().expect("parse datasets");
+        let caesar = datasets.commander(1).expect("commander 1");
+        assert_eq!(caesar.rarity, Some(CommanderRarity::Legendary));
+        assert_eq!(caesar.prime, Some(false));
+        assert_eq!(
+            caesar.skills[0],
+            CommanderSkill {
+                slot: 1,
+                id: 22,
+                expert_id: Some(61)
+            }
+        );
+        assert_eq!(
+            datasets.inscription(1101).map(|i| i.rarity),
+            Some(InscriptionRarity::Special)
+        );
+        assert_eq!(datasets.armament(2004).map(|a| a.percent), Some(true));
+        assert!(datasets.loot_items().count() > 0);
+    }
+
+    #[test]
+    fn from_dir_matches_embedded() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../datasets");
+        let loaded = Datasets::from_dir(&dir).expect("load datasets");
+        assert_eq!(loaded, Datasets::embedded().expect("parse datasets"));
+    }
+
+    #[test]
+    fn from_dir_reports_missing_files() {
+        let err = Datasets::from_dir(Path::new("/nonexistent-datasets")).unwrap_err();
+        assert!(matches!(err, DatasetError::Io { .. }));
+    }
+
+    #[test]
+    fn embedded_datasets_parse() {
+        let datasets = Datasets::embedded().expect("parse datasets");
+        let caesar = datasets.commander(1).expect("commander 1");
+        assert_eq!(caesar.name.get(Locale::En), Some("Julius Caesar"));
+        assert_eq!(
+            datasets.formation(1).and_then(|f| f.name.get(Locale::En)),
+            Some("Arch Formation")
+        );
+        assert_eq!(
+            datasets.equipment_slot(1).and_then(|s| s.get(Locale::De)),
+            Some("Waffen")
+        );
+        assert_eq!(
+            datasets
+                .equipment_item(20001)
+                .and_then(|i| i.name.get(Locale::En)),
+            Some("Sacred Dominion")
+        );
+        assert_eq!(
+            datasets
+                .inscription(101)
+                .and_then(|i| i.name.get(Locale::En)),
+            Some("Warcry")
+        );
+        assert_eq!(
+            datasets.armament(2004).and_then(|a| a.name.get(Locale::En)),
+            Some("Gold Gathering Speed")
+        );
+        assert_eq!(
+            datasets.loot(1, 9).and_then(|l| l.name.get(Locale::En)),
+            Some("Crystals")
+        );
+    }
+}
@@ -0,0 +1,263 @@
+//! Attach localized names to processed mail reports.
+//!
+//! Enrichment adds `name`-style fields next to the numeric ids already present
+//! in processor output. Unknown ids get a null name so the output shape stays
+//! stable; ids and other fields are never modified.
+
+use serde_json::{Map, Value};
+
+use crate::{Datasets, Locale, LocalizedText};
+
+/// Enrich a processed Battle report in place.
+pub fn enrich_battle(processed: &mut Value, datasets: &Datasets, locale: Locale) {
+    let enricher = Enricher { datasets, locale };
+    if let Some(sender) = processed.get_mut("sender") {
+        enricher.battle_player(sender);
+    }
+    if let Some(Value::Array(opponents)) = processed.get_mut("opponents") {
+        for opponent in opponents {
+            enricher.battle_player(opponent);
+            if let Some(Value::Array(loot)) = opponent.pointer_mut("/npc/loot") {
+                for entry in loot {
+                    enricher.loot(entry);
+                }
+            }
+        }
+    }
+    if let Some(Value::Array(loot)) = processed.pointer_mut("/npc/loot") {
+        for entry in loot {
+            enricher.loot(entry);
+        }
+    }
+}
+
+/// Enrich a processed DuelBattle2 report in place.
+pub fn enrich_duelbattle2(processed: &mut Value, datasets: &Datasets, locale: Locale) {
+    let enricher = Enricher { datasets, locale };
+    for side in ["sender", "opponent"] {
+        let Some(player) = processed.get_mut(side) else {
+            continue;
+        };
+        for key in ["primary_commander", "secondary_commander"] {
+            if let Some(commander) = player.get_mut(key) {
+                enricher.commander(commander);
+            }
+        }
+        if let Some(Value::Array(buffs)) = player.get_mut("buffs") {
+            for buff in buffs {
+                enricher.named(buff, "id", "name", |id| {
+                    datasets.armament(id).map(|entry| &entry.name)
+                });
+            }
+        }
+    }
+}
+
+struct Enricher<'a> {
+    datasets: &'a Datasets,
+    locale: Locale,
+}
+
+impl Enricher<'_> {
+    fn battle_player(&self, player: &mut Value) {
+        self.commanders(player);
+        if let Some(Value::Array(participants)) = player.get_mut("participants") {
+            for participant in participants {
+                self.commanders(participant);
+            }
+        }
+    }
+
+    fn commanders(&self, player: &mut Value) {
+        let Some(commanders) = player.get_mut("commanders") else {
+            return;
+        };
+        for key in ["primary", "secondary"] {
+            if let Some(commander) = commanders.get_mut(key) {
+                self.commander(commander);
+            }
+        }
+    }
+
+    fn commander(&self, commander: &mut Value) {
+        let datasets = self.datasets;
+        self.named(commander, "id", "name", |id| {
+            datasets.commander(id).map(|entry| &entry.name)
+        });
+        if commander.get("formation").is_some() {
+            self.named(commander, "formation", "formation_name", |id| {
+                datasets.formation(id).map(|entry| &entry.name)
+            });
+        }
+        if let Some(Value::Array(slots)) = commander.get_mut("equipment_slots") {
+            for slot in slots {
+                self.named(slot, "id", "name", |id| {
+                    datasets.equipment_item(id).map(|entry| &entry.name)
+                });
+                self.named(slot, "slot", "slot_name", |slot| {
+                    datasets.equipment_slot(slot)
+                });
+            }
+        }
+        if let Some(Value::Array(armaments)) = commander.get_mut("armaments") {
+            for armament in armaments {
+                if let Some(Value::Array(inscriptions)) = armament.get_mut("inscriptions") {
+                    for inscription in inscriptions {
+                        self.named(inscription, "id", "name", |id| {
+                            datasets.inscription(id).map(|entry| &entry.name)
+                        });
+                    }
+                }
+                if let Some(Value::Array(stats)) = armament.get_mut("stats") {
+                    for stat in stats {
+                        self.named(stat, "id", "name", |id| {
+                            datasets.armament(id).map(|entry| &entry.name)
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    fn loot(&self, entry: &mut Value) {
+        let Some(object) = entry.as_object_mut() else {
+            return;
+        };
+        let loot_type = object.get("type").and_then(Value::as_u64);
+        let sub_type = object.get("sub_type").and_then(Value::as_u64);
+        let name = match (loot_type, sub_type) {
+            (Some(loot_type), Some(sub_type)) => self
+                .datasets
+                .loot(loot_type, sub_type)
+                .map(|entry| &entry.name),
+            _ => None,
+        };
+        insert_name(object, "name", name, self.locale);
+    }
+
+    /// Read a numeric id field and insert the resolved name under `name_key`.
+    fn named<'a>(
+        &self,
+        value: &mut Value,
+        id_key: &str,
+        name_key: &str,
+        lookup: impl FnOnce(u64) -> Option<&'a LocalizedText>,
+    ) {
+        let Some(object) = value.as_object_mut() else {
+            return;
+        };
+        let name = object.get(id_key).and_then(Value::as_u64).and_then(lookup);
+        insert_name(object, name_key, name, self.locale);
+    }
+}
+
+fn insert_name(
+    object: &mut Map<String, Value>,
+    key: &str,
+    name: Option<&LocalizedText>,
+    locale: Locale,
+) {
+    let name = name
+        .and_then(|text| text.get(locale))
+        .map(|text| Value::String(text.to_string()))
+        .unwrap_or(Value::Null);
+    object.insert(key.to_string(), name);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn datasets() -> Datasets {
+        Datasets::embedded().expect("parse datasets")
+    }
+
+    #[test]
+    fn enrich_battle_names_commanders_and_loot() {
+        let mut processed = json!({
+            "sender": {
+                "commanders": {
+                    "primary": {
+                        "id": 1,
+                        "formation": 1,
+                        "equipment_slots": [{ "slot": 1, "id": 20001 }],
+                        "armaments": [{
+                            "inscriptions": [{ "id": 101 }],
+                            "stats": [{ "id": 2004, "value": 0.07 }]
+                        }]
+                    },
+                    "secondary": { "id": 999999, "formation": null }
+                },
+                "participants": [
+                    { "commanders": { "primary": { "id": 1 }, "secondary": { "id": 0 } } }
+                ]
+            },
+            "opponents": [{
+                "commanders": { "primary": { "id": 1 } },
+                "npc": { "loot": [{ "type": 1, "sub_type": 9, "value": 5 }] }
+            }],
+            "npc": { "loot": [{ "type": 1, "sub_type": 9, "value": 5 }] }
+        });
+        enrich_battle(&mut processed, &datasets(), Locale::En);
+
+        let primary = &processed["sender"]["commanders"]["primary"];
+        assert_eq!(primary["name"], json!("Julius Caesar"));
+        assert_eq!(primary["formation_name"], json!("Arch Formation"));
+        assert_eq!(
+            primary["equipment_slots"][0]["name"],
+            json!("Sacred Dominion")
+        );
+        assert_eq!(primary["equipment_slots"][0]["slot_name"], json!("Weapons"));
+        assert_eq!(
+            primary["armaments"][0]["inscriptions"][0]["name"],
+            json!("Warcry")
+        );
+        assert_eq!(
+            primary["armaments"][0]["stats"][0]["name"],
+            json!("Gold Gathering Speed")
+        );
+        let secondary = &processed["sender"]["commanders"]["secondary"];
+        assert_eq!(secondary["name"], Value::Null);
+        assert_eq!(secondary["formation_name"], Value::Null);
+        assert_eq!(
+            processed["sender"]["participants"][0]["commanders"]["primary"]["name"],
+            json!("Julius Caesar")
+        );
+        assert!(
+            processed["sender"]["participants"][0]["commanders"]["primary"]
+                .get("formation_name")
+                .is_none()
+        );
+        assert_eq!(
+            processed["opponents"][0]["npc"]["loot"][0]["name"],
+            json!("Crystals")
+        );
+        assert_eq!(processed["npc"]["loot"][0]["name"], json!("Crystals"));
+    }
+
+    #[test]
+    fn enrich_duelbattle2_uses_requested_locale() {
+        let mut processed = json!({
+            "sender": {
+                "primary_commander": { "id": 1 },
+                "secondary_commander": { "id": 3 },
+                "buffs": [{ "id": 2004, "value": 0.1 }]
+            },
+            "opponent": { "primary_commander": { "id": 1 } }
+        });
+        enrich_duelbattle2(&mut processed, &datasets(), Locale::De);
+        assert_eq!(
+            processed["sender"]["primary_commander"]["name"],
+            json!("Julius Cäsar")
+        );
+        assert_eq!(
+            processed["sender"]["buffs"][0]["name"],
+            json!("Goldsammelgeschwindigkeit")
+        );
+        assert_eq!(
+            processed["opponent"]["primary_commander"]["name"],
+            json!("Julius Cäsar")
+        );
+    }
+}
@@ -0,0 +1,58 @@
+//! Error types for dataset loading.
+
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+/// Errors raised when a dataset file cannot be read or parsed.
+#[derive(Debug)]
+pub enum DatasetError {
+    /// A dataset file could not be read from disk.
+    Io {
+        /// The dataset file path.
+        path: PathBuf,
+        /// The underlying I/O error.
+        source: io::Error,
+    },
+    /// A dataset file was not valid YAML for its expected shape.
+    Parse {
+        /// The dataset file name.
+        file: &'static str,
+        /// The underlying YAML error.
+        source: serde_yaml::Error,
+    },
+    /// A catalog name rule could not be parsed.
+    InvalidRule {
+        /// The rule as written.
+        rule: String,
+        /// Why the rule was rejected.
+        reason: &'static str,
+    },
+}
+
+impl fmt::Display for DatasetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DatasetError::Io { path, source } => {
+                write!(f, "failed to read dataset {}: {source}", path.display())
+            }
+            DatasetError::Parse { file, source } => {
+                write!(f, "failed to parse dataset {file}: {source}")
+            }
+            DatasetError::InvalidRule { rule, reason } => {
+                write!(f, "invalid name rule `{rule}`: {reason}")
+            }
+        }
+    }
+}
+
+impl Error for DatasetError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            DatasetError::Io { source, .. } => Some(source),
+            DatasetError::Parse { source, .. } => Some(source),
+            DatasetError::InvalidRule { .. } => None,
+        }
+    }
+}
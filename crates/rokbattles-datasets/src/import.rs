@@ -0,0 +1,796 @@
+//! Update dataset `name` blocks from game string catalogs.
+//!
+//! Files are edited line by line so comments, ordering and non-name fields
+//! stay untouched; only the locale lines of matched blocks are rewritten.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+use std::str::FromStr;
+
+use serde::Serialize;
+
+use crate::dataset::{parse, read_file};
+use crate::{DatasetError, Datasets, Locale};
+
+/// Localized strings keyed by catalog key, for each locale.
+pub type Catalogs = BTreeMap<Locale, BTreeMap<String, String>>;
+
+/// A dataset name block that can be filled from string catalogs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NameTarget {
+    /// `commanders.<id>.name`
+    CommanderName,
+    /// `commanders.<id>.nickname`
+    CommanderNickname,
+    /// `armaments.<id>.name`
+    Armament,
+    /// `equipment.item.<id>.name`
+    EquipmentItem,
+    /// `formations.<id>.name`
+    Formation,
+    /// `inscriptions.<id>.name`
+    Inscription,
+    /// `loot.<type>.<sub_type>.name`
+    ///
+    /// Rules name the loot type in the target (`loot.<type>.name`) and map
+    /// catalog keys onto sub types.
+    Loot,
+}
+
+impl NameTarget {
+    /// Every supported target.
+    pub const ALL: [NameTarget; 7] = [
+        NameTarget::CommanderName,
+        NameTarget::CommanderNickname,
+        NameTarget::Armament,
+        NameTarget::EquipmentItem,
+        NameTarget::Formation,
+        NameTarget::Inscription,
+        NameTarget::Loot,
+    ];
+
+    /// The target as written in rules (for example `equipment.item.name`).
+    pub fn as_str(self) -> &'static str {
+        match self {
+            NameTarget::CommanderName => "commanders.name",
+            NameTarget::CommanderNickname => "commanders.nickname",
+            NameTarget::Armament => "armaments.name",
+            NameTarget::EquipmentItem => "equipment.item.name",
+            NameTarget::Formation => "formations.name",
+            NameTarget::Inscription => "inscriptions.name",
+            NameTarget::Loot => "loot.name",
+        }
+    }
+
+    /// The dataset file holding the target.
+    pub fn file(self) -> &'static str {
+        match self {
+            NameTarget::CommanderName | NameTarget::CommanderNickname => "commanders.yaml",
+            NameTarget::Armament => "armaments.yaml",
+            NameTarget::EquipmentItem => "equipment.yaml",
+            NameTarget::Formation => "formations.yaml",
+            NameTarget::Inscription => "inscriptions.yaml",
+            NameTarget::Loot => "loot.yaml",
+        }
+    }
+
+    fn section(self) -> &'static [&'static str] {
+        match self {
+            NameTarget::CommanderName | NameTarget::CommanderNickname => &["commanders"],
+            NameTarget::Armament => &["armaments"],
+            NameTarget::EquipmentItem => &["equipment", "item"],
+            NameTarget::Formation => &["formations"],
+            NameTarget::Inscription => &["inscriptions"],
+            NameTarget::Loot => &["loot"],
+        }
+    }
+
+    fn field(self) -> &'static str {
+        match self {
+            NameTarget::CommanderNickname => "nickname",
+            _ => "name",
+        }
+    }
+
+    /// Whether a new entry holding only this block still parses.
+    ///
+    /// Inscriptions also need a rarity and nicknames belong to an existing
+    /// commander, so those ids are reported instead of added.
+    fn can_add_entry(self) -> bool {
+        !matches!(
+            self,
+            NameTarget::CommanderNickname | NameTarget::Inscription
+        )
+    }
+}
+
+impl fmt::Display for NameTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Parse a rule target, returning the loot type for `loot.<type>.name`.
+fn parse_target(target: &str) -> Option<(NameTarget, Option<u64>)> {
+    if let Some(loot_type) = target
+        .strip_prefix("loot.")
+        .and_then(|rest| rest.strip_suffix(".name"))
+    {
+        return Some((NameTarget::Loot, Some(loot_type.parse().ok()?)));
+    }
+    NameTarget::ALL
+        .into_iter()
+        .filter(|candidate| *candidate != NameTarget::Loot)
+        .find(|candidate| candidate.as_str() == target)
+        .map(|target| (target, None))
+}
+
+/// Maps catalog keys such as `N_12` onto dataset ids.
+///
+/// Written as `TARGET=PREFIX{id}SUFFIX[@OFFSET]`, for example
+/// `equipment.item.name=N_{id}@20000` maps `N_12` to equipment item 20012.
+/// Loot rules fix the loot type in the target, so `loot.2.name=Gem_{id}`
+/// maps `Gem_26` to loot type 2, sub type 26.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NameRule {
+    target: NameTarget,
+    parent: Option<u64>,
+    prefix: String,
+    suffix: String,
+    offset: u64,
+}
+
+impl NameRule {
+    /// The dataset block the rule fills.
+    pub fn target(&self) -> NameTarget {
+        self.target
+    }
+
+    /// The parent key of two-level targets (the loot type for loot rules).
+    pub fn parent(&self) -> Option<u64> {
+        self.parent
+    }
+
+    /// Resolve a catalog key to a dataset id.
+    pub fn id_for_key(&self, key: &str) -> Option<u64> {
+        let digits = key
+            .strip_prefix(self.prefix.as_str())?
+            .strip_suffix(self.suffix.as_str())?;
+        if digits.is_empty() || !digits.bytes().all(|byte| byte.is_ascii_digit()) {
+            return None;
+        }
+        digits.parse::<u64>().ok()?.checked_add(self.offset)
+    }
+}
+
+impl FromStr for NameRule {
+    type Err = DatasetError;
+
+    fn from_str(rule: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: &'static str| DatasetError::InvalidRule {
+            rule: rule.to_string(),
+            reason,
+        };
+        let (target, pattern) = rule
+            .split_once('=')
+            .ok_or(invalid("expected TARGET=PATTERN"))?;
+        let (target, parent) = parse_target(target.trim()).ok_or(invalid("unknown target"))?;
+        let (pattern, offset) = match pattern.rsplit_once('@') {
+            Some((pattern, offset)) => (
+                pattern,
+                offset
+                    .parse::<u64>()
+                    .map_err(|_| invalid("offset must be a non-negative integer"))?,
+            ),
+            None => (pattern, 0),
+        };
+        let (prefix, suffix) = pattern
+            .split_once("{id}")
+            .ok_or(invalid("pattern must contain {id}"))?;
+        Ok(Self {
+            target,
+            parent,
+            prefix: prefix.to_string(),
+            suffix: suffix.to_string(),
+            offset,
+        })
+    }
+}
+
+/// How a locale line changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateKind {
+    /// The locale was missing or empty.
+    Added,
+    /// The locale had different text.
+    Changed,
+}
+
+/// A single locale line written to a dataset file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NameUpdate {
+    /// The updated block.
+    pub target: NameTarget,
+    /// The parent key of two-level targets, such as the loot type.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent: Option<u64>,
+    /// The dataset id.
+    pub id: u64,
+    /// The locale code.
+    pub locale: Locale,
+    /// Whether the text was added or changed.
+    pub kind: UpdateKind,
+    /// The previous text, when changed.
+    pub old: Option<String>,
+    /// The new text.
+    pub new: String,
+}
+
+/// A catalog id that has no dataset entry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NewEntry {
+    /// The target block.
+    pub target: NameTarget,
+    /// The parent key of two-level targets, such as the loot type.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent: Option<u64>,
+    /// The dataset id.
+    pub id: u64,
+    /// Whether the entry was written (`false` when not requested or not possible).
+    pub added: bool,
+}
+
+/// Outcome of applying name rules.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ImportReport {
+    /// Locale lines added or changed in existing entries.
+    pub updates: Vec<NameUpdate>,
+    /// Catalog ids missing from the datasets.
+    pub new_entries: Vec<NewEntry>,
+}
+
+impl ImportReport {
+    /// Whether any dataset text was modified.
+    pub fn is_empty(&self) -> bool {
+        self.updates.is_empty() && !self.new_entries.iter().any(|entry| entry.added)
+    }
+}
+
+/// Options for [`import_names_dir`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ImportOptions {
+    /// Append entries for catalog ids that are missing from the datasets.
+    pub add_new: bool,
+    /// Compute the report without writing files.
+    pub dry_run: bool,
+}
+
+/// Apply name rules to every affected dataset file in a directory.
+pub fn import_names_dir(
+    dir: &Path,
+    rules: &[NameRule],
+    catalogs: &Catalogs,
+    options: ImportOptions,
+) -> Result<ImportReport, DatasetError> {
+    let mut report = ImportReport::default();
+    for file in Datasets::FILES {
+        let file_rules: Vec<&NameRule> = rules
+            .iter()
+            .filter(|rule| rule.target.file() == file)
+            .collect();
+        if file_rules.is_empty() {
+            continue;
+        }
+        let text = read_file(dir, file)?;
+        let mut updated = text.clone();
+        for rule in file_rules {
+            updated = import_names(&updated, rule, catalogs, options.add_new, &mut report)?;
+        }
+        if !options.dry_run && updated != text {
+            let path = dir.join(file);
+            fs::write(&path, updated).map_err(|source| DatasetError::Io { path, source })?;
+        }
+    }
+    Ok(report)
+}
+
+/// Apply a single name rule to the text of its dataset file.
+pub fn import_names(
+    text: &str,
+    rule: &NameRule,
+    catalogs: &Catalogs,
+    add_new: bool,
+    report: &mut ImportReport,
+) -> Result<String, DatasetError> {
+    let target = rule.target;
+    let parent = rule.parent;
+    let current: serde_yaml::Value = parse(target.file(), text)?;
+    let mut document = Document::new(text);
+    let section = target
+        .section()
+        .iter()
+        .try_fold(&current, |value, key| value.get(*key))
+        .and_then(|section| match parent {
+            Some(parent) => section.get(serde_yaml::Value::from(parent)),
+            None => Some(section),
+        });
+
+    for (id, translations) in collect_translations(rule, catalogs) {
+        let entry = section.and_then(|section| section.get(serde_yaml::Value::from(id)));
+        let Some(entry) = entry else {
+            let added = add_new
+                && target.can_add_entry()
+                && translations.contains_key(&Locale::FALLBACK)
+                && document.add_entry(target, parent, id, &translations);
+            report.new_entries.push(NewEntry {
+                target,
+                parent,
+                id,
+                added,
+            });
+            continue;
+        };
+
+        let existing = entry.get(target.field());
+        let mut changes = BTreeMap::new();
+        for (locale, new) in &translations {
+            let old = existing
+                .and_then(|names| names.get(locale.code()))
+                .and_then(serde_yaml::Value::as_str)
+                .filter(|old| !old.is_empty());
+            let kind = match old {
+                Some(old) if old == new => continue,
+                Some(_) => UpdateKind::Changed,
+                None => UpdateKind::Added,
+            };
+            report.updates.push(NameUpdate {
+                target,
+                parent,
+                id,
+                locale: *locale,
+                kind,
+                old: old.map(str::to_string),
+                new: new.clone(),
+            });
+            changes.insert(*locale, new.as_str());
+        }
+        if !changes.is_empty() {
+            document.update_names(target, parent, id, &changes);
+        }
+    }
+
+    Ok(document.into_text())
+}
+
+/// Group catalog strings by dataset id for a rule, skipping empty text.
+fn collect_translations(
+    rule: &NameRule,
+    catalogs: &Catalogs,
+) -> BTreeMap<u64, BTreeMap<Locale, String>> {
+    let mut translations: BTreeMap<u64, BTreeMap<Locale, String>> = BTreeMap::new();
+    for (locale, strings) in catalogs {
+        for (key, value) in strings {
+            let Some(id) = rule.id_for_key(key) else {
+                continue;
+            };
+            let value = value.trim();
+            if !value.is_empty() {
+                translations
+                    .entry(id)
+                    .or_default()
+                    .insert(*locale, value.to_string());
+            }
+        }
+    }
+    translations
+}
+
+/// Dataset file lines with helpers for locating block-style mappings.
+struct Document {
+    lines: Vec<String>,
+    trailing_newline: bool,
+}
+
+impl Document {
+    fn new(text: &str) -> Self {
+        Self {
+            lines: text.lines().map(str::to_string).collect(),
+            trailing_newline: text.ends_with('\n'),
+        }
+    }
+
+    fn into_text(self) -> String {
+        let mut text = self.lines.join("\n");
+        if self.trailing_newline {
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Find `key:` at `indent` within `range`, returning the header line and its body.
+    fn child(
+        &self,
+        range: Range<usize>,
+        indent: usize,
+        key: &str,
+    ) -> Option<(usize, Range<usize>)> {
+        let header = range.clone().find(|&index| {
+            let line = &self.lines[index];
+            is_content(line) && indent_of(line) == indent && key_of(line) == Some(key)
+        })?;
+        let end = (header + 1..range.end)
+            .find(|&index| {
+                is_content(&self.lines[index]) && indent_of(&self.lines[index]) <= indent
+            })
+            .unwrap_or(range.end);
+        Some((header, header + 1..end))
+    }
+
+    /// Locate the body holding a target's entries and the indent of entry keys.
+    fn section(&self, target: NameTarget, parent: Option<u64>) -> Option<(Range<usize>, usize)> {
+        let parent = parent.map(|parent| parent.to_string());
+        let mut range = 0..self.lines.len();
+        let mut indent = 0;
+        for key in target.section().iter().copied().chain(parent.as_deref()) {
+            let (_, body) = self.child(range, indent, key)?;
+            range = body;
+            indent += 2;
+        }
+        Some((range, indent))
+    }
+
+    fn update_names(
+        &mut self,
+        target: NameTarget,
+        parent: Option<u64>,
+        id: u64,
+        changes: &BTreeMap<Locale, &str>,
+    ) {
+        let Some((section, indent)) = self.section(target, parent) else {
+            return;
+        };
+        let Some((entry_header, entry)) = self.child(section, indent, &id.to_string()) else {
+            return;
+        };
+        let locale_indent = indent + 4;
+        let Some((_, names)) = self.child(entry.clone(), indent + 2, target.field()) else {
+            // The entry exists without this field; append it to the entry body.
+            let insert_at = last_content_line(&self.lines, entry_header..entry.end) + 1;
+            let mut block = vec![format!("{}{}:", pad(indent + 2), target.field())];
+            block.extend(
+                changes
+                    .iter()
+                    .map(|(locale, text)| locale_line(locale_indent, locale.code(), text)),
+            );
+            self.lines.splice(insert_at..insert_at, block);
+            return;
+        };
+
+        let locale_lines: Vec<usize> = names
+            .clone()
+            .filter(|&index| {
+                let line = &self.lines[index];
+                is_content(line) && indent_of(line) == locale_indent
+            })
+            .collect();
+        let span = match (locale_lines.first(), locale_lines.last()) {
+            (Some(first), Some(last)) => *first..*last + 1,
+            _ => names.start..names.start,
+        };
+
+        let mut existing: Vec<(String, String)> = locale_lines
+            .iter()
+            .filter_map(|&index| {
+                let line = &self.lines[index];
+                key_of(line).map(|code| (code.to_string(), line.clone()))
+            })
+            .collect();
+        for (locale, text) in changes {
+            let line = locale_line(locale_indent, locale.code(), text);
+            match existing.iter_mut().find(|(code, _)| code == locale.code()) {
+                Some(entry) => entry.1 = line,
+                None => existing.push((locale.code().to_string(), line)),
+            }
+        }
+        // Keep supported locales in dataset order, then anything unrecognised.
+        existing.sort_by_key(|(code, _)| {
+            Locale::ALL
+                .iter()
+                .position(|locale| locale.code() == code)
+                .unwrap_or(Locale::ALL.len())
+        });
+        self.lines
+            .splice(span, existing.into_iter().map(|(_, line)| line));
+    }
+
+    /// Insert a new entry in id order. Returns `false` when the section is missing.
+    fn add_entry(
+        &mut self,
+        target: NameTarget,
+        parent: Option<u64>,
+        id: u64,
+        translations: &BTreeMap<Locale, String>,
+    ) -> bool {
+        let Some((section, indent)) = self.section(target, parent) else {
+            return false;
+        };
+        let mut block = vec![
+            format!("{}{id}:", pad(indent)),
+            format!("{}{}:", pad(indent + 2), target.field()),
+        ];
+        block.extend(
+            translations
+                .iter()
+                .map(|(locale, text)| locale_line(indent + 4, locale.code(), text)),
+        );
+
+        let next_entry = section.clone().find(|&index| {
+            let line = &self.lines[index];
+            is_content(line)
+                && indent_of(line) == indent
+                && key_of(line)
+                    .and_then(|key| key.parse::<u64>().ok())
+                    .is_some_and(|other| other > id)
+        });
+        match next_entry {
+            Some(mut insert_at) => {
+                // Keep comments attached to the following entry above it.
+                while insert_at > section.start && is_comment(&self.lines[insert_at - 1]) {
+                    insert_at -= 1;
+                }
+                block.push(String::new());
+                self.lines.splice(insert_at..insert_at, block);
+            }
+            None => {
+                let insert_at = last_content_line(&self.lines, section.clone()) + 1;
+                block.insert(0, String::new());
+                self.lines.splice(insert_at..insert_at, block);
+            }
+        }
+        true
+    }
+}
+
+fn last_content_line(lines: &[String], range: Range<usize>) -> usize {
+    let start = range.start;
+    range
+        .rev()
+        .find(|&index| is_content(&lines[index]))
+        .unwrap_or(start.saturating_sub(1))
+}
+
+fn is_comment(line: &str) -> bool {
+    line.trim_start().starts_with('#')
+}
+
+fn is_content(line: &str) -> bool {
+    !line.trim().is_empty() && !is_comment(line)
+}
+
+fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+fn key_of(line: &str) -> Option<&str> {
+    let (key, _) = line.trim_start().split_once(':')?;
+    Some(key.trim())
+}
+
+fn pad(indent: usize) -> String {
+    " ".repeat(indent)
+}
+
+/// Format a locale line with a double-quoted YAML scalar.
+fn locale_line(indent: usize, code: &str, text: &str) -> String {
+    // JSON string escapes are valid inside YAML double-quoted scalars.
+    let quoted = serde_json::to_string(text).unwrap_or_else(|_| format!("\"{text}\""));
+    format!("{}{code}: {quoted}", pad(indent))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FORMATIONS: &str = "formations:\n  # null = no formation\n\n  1:\n    name:\n      de: \"Keil\"\n      en: \"Wedge\"\n\n  # hollow square\n  5:\n    name:\n      en: \"Square\"\n";
+
+    fn catalogs(entries: &[(Locale, &str, &str)]) -> Catalogs {
+        let mut catalogs = Catalogs::new();
+        for (locale, key, value) in entries {
+            catalogs
+                .entry(*locale)
+                .or_default()
+                .insert(key.to_string(), value.to_string());
+        }
+        catalogs
+    }
+
+    #[test]
+    fn name_rule_parses_pattern_and_offset() {
+        let rule: NameRule = "equipment.item.name=N_{id}@20000".parse().unwrap();
+        assert_eq!(rule.target(), NameTarget::EquipmentItem);
+        assert_eq!(rule.parent(), None);
+        assert_eq!(rule.id_for_key("N_12"), Some(20012));
+        assert_eq!(rule.id_for_key("N_12a"), None);
+        assert_eq!(rule.id_for_key("D_12"), None);
+
+        let rule: NameRule = "commanders.nickname=hero_{id}_title".parse().unwrap();
+        assert_eq!(rule.id_for_key("hero_7_title"), Some(7));
+        assert!("unknown=N_{id}".parse::<NameRule>().is_err());
+
+        let rule: NameRule = "loot.2.name=Gem_{id}".parse().unwrap();
+        assert_eq!(rule.target(), NameTarget::Loot);
+        assert_eq!(rule.parent(), Some(2));
+        assert_eq!(rule.id_for_key("Gem_26"), Some(26));
+        assert!("loot.name=Gem_{id}".parse::<NameRule>().is_err());
+        assert!("loot.x.name=Gem_{id}".parse::<NameRule>().is_err());
+        assert!("formations.name=N_".parse::<NameRule>().is_err());
+        assert!("formations.name=N_{id}@x".parse::<NameRule>().is_err());
+    }
+
+    #[test]
+    fn import_names_updates_existing_blocks_in_locale_order() {
+        let rule: NameRule = "formations.name=F_{id}".parse().unwrap();
+        let catalogs = catalogs(&[
+            (Locale::En, "F_1", "Wedge"),
+            (Locale::De, "F_1", "Keilformation"),
+            (Locale::Ar, "F_1", "إسفين"),
+            (Locale::Fr, "F_5", ""),
+        ]);
+        let mut report = ImportReport::default();
+        let text = import_names(FORMATIONS, &rule, &catalogs, false, &mut report).unwrap();
+
+        assert_eq!(
+            text,
+            "formations:\n  # null = no formation\n\n  1:\n    name:\n      ar: \"إسفين\"\n      de: \"Keilformation\"\n      en: \"Wedge\"\n\n  # hollow square\n  5:\n    name:\n      en: \"Square\"\n"
+        );
+        assert_eq!(
+            report
+                .updates
+                .iter()
+                .map(|update| (update.locale, update.kind, update.old.as_deref()))
+                .collect::<Vec<_>>(),
+            [
+                (Locale::Ar, UpdateKind::Added, None),
+                (Locale::De, UpdateKind::Changed, Some("Keil")),
+            ]
+        );
+        assert!(report.new_entries.is_empty());
+    }
+
+    #[test]
+    fn import_names_adds_new_entries_in_id_order() {
+        let rule: NameRule = "formations.name=F_{id}".parse().unwrap();
+        let catalogs = catalogs(&[
+            (Locale::En, "F_3", "Arch \"Formation\""),
+            (Locale::En, "F_9", "Line"),
+            (Locale::De, "F_9", "Linie"),
+        ]);
+        let mut report = ImportReport::default();
+        let text = import_names(FORMATIONS, &rule, &catalogs, true, &mut report).unwrap();
+
+        assert_eq!(
+            text,
+            "formations:\n  # null = no formation\n\n  1:\n    name:\n      de: \"Keil\"\n      en: \"Wedge\"\n\n  3:\n    name:\n      en: \"Arch \\\"Formation\\\"\"\n\n  # hollow square\n  5:\n    name:\n      en: \"Square\"\n\n  9:\n    name:\n      de: \"Linie\"\n      en: \"Line\"\n"
+        );
+        assert_eq!(
+            report.new_entries,
+            [
+                NewEntry {
+                    target: NameTarget::Formation,
+                    parent: None,
+                    id: 3,
+                    added: true
+                },
+                NewEntry {
+                    target: NameTarget::Formation,
+                    parent: None,
+                    id: 9,
+                    added: true
+                },
+            ]
+        );
+        let parsed: crate::dataset::FormationsFile = parse("formations.yaml", &text).unwrap();
+        assert_eq!(
+            parsed.formations[&3].name.get(Locale::En),
+            Some("Arch \"Formation\"")
+        );
+    }
+
+    #[test]
+    fn import_names_reports_entries_it_cannot_add() {
+        let rule: NameRule = "inscriptions.name=I_{id}".parse().unwrap();
+        let catalogs = catalogs(&[(Locale::En, "I_101", "Warcry")]);
+        let mut report = ImportReport::default();
+        let text = "inscriptions:\n  102:\n    name:\n      en: \"x\"\n    rarity: common\n";
+        let updated = import_names(text, &rule, &catalogs, true, &mut report).unwrap();
+        assert_eq!(updated, text);
+        assert_eq!(
+            report.new_entries,
+            [NewEntry {
+                target: NameTarget::Inscription,
+                parent: None,
+                id: 101,
+                added: false
+            }]
+        );
+        assert!(report.is_empty());
+    }
+
+    #[test]
+    fn import_names_adds_missing_field_to_existing_entry() {
+        let rule: NameRule = "commanders.nickname=T_{id}".parse().unwrap();
+        let catalogs = catalogs(&[(Locale::En, "T_1", "The Uncrowned Emperor")]);
+        let mut report = ImportReport::default();
+        let text = "commanders:\n  1:\n    name:\n      en: \"Julius Caesar\"\n\n  2:\n    name:\n      en: \"Charles Martel\"\n";
+        let updated = import_names(text, &rule, &catalogs, false, &mut report).unwrap();
+        assert_eq!(
+            updated,
+            "commanders:\n  1:\n    name:\n      en: \"Julius Caesar\"\n    nickname:\n      en: \"The Uncrowned Emperor\"\n\n  2:\n    name:\n      en: \"Charles Martel\"\n"
+        );
+        assert_eq!(report.updates.len(), 1);
+    }
+
+    #[test]
+    fn import_names_updates_and_adds_loot_under_its_type() {
+        let rule: NameRule = "loot.2.name=Gem_{id}".parse().unwrap();
+        let catalogs = catalogs(&[
+            (Locale::En, "Gem_26", "10 Gems"),
+            (Locale::De, "Gem_26", "10 Edelsteine"),
+            (Locale::En, "Gem_27", "20 Gems"),
+        ]);
+        let mut report = ImportReport::default();
+        let text = "loot:\n  1:\n    26:\n      name:\n        en: \"Crystals\"\n\n  2:\n    26:\n      name:\n        en: \"10 Gems\"\n";
+        let updated = import_names(text, &rule, &catalogs, true, &mut report).unwrap();
+
+        assert_eq!(
+            updated,
+            "loot:\n  1:\n    26:\n      name:\n        en: \"Crystals\"\n\n  2:\n    26:\n      name:\n        de: \"10 Edelsteine\"\n        en: \"10 Gems\"\n\n    27:\n      name:\n        en: \"20 Gems\"\n"
+        );
+        assert_eq!(
+            report
+                .updates
+                .iter()
+                .map(|update| (update.parent, update.id, update.locale))
+                .collect::<Vec<_>>(),
+            [(Some(2), 26, Locale::De)]
+        );
+        assert_eq!(
+            report.new_entries,
+            [NewEntry {
+                target: NameTarget::Loot,
+                parent: Some(2),
+                id: 27,
+                added: true
+            }]
+        );
+        let parsed: crate::dataset::LootFile = parse("loot.yaml", &updated).unwrap();
+        assert_eq!(parsed.loot[&2][&27].name.get(Locale::En), Some("20 Gems"));
+    }
+
+    #[test]
+    fn import_names_leaves_embedded_equipment_unchanged_for_matching_catalog() {
+        let datasets = Datasets::embedded().unwrap();
+        let mut catalogs = Catalogs::new();
+        for (id, item) in datasets.equipment_items() {
+            for (code, text) in item.name.iter() {
+                if let Some(locale) = Locale::from_code(code) {
+                    catalogs
+                        .entry(locale)
+                        .or_default()
+                        .insert(format!("N_{}", id - 20000), text.to_string());
+                }
+            }
+        }
+        let rule: NameRule = "equipment.item.name=N_{id}@20000".parse().unwrap();
+        let mut report = ImportReport::default();
+        let text = crate::dataset::EMBEDDED[2];
+        let updated = import_names(text, &rule, &catalogs, true, &mut report).unwrap();
+        assert!(report.is_empty(), "{report:?}");
+        assert_eq!(updated, text);
+    }
+}
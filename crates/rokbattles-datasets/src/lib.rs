@@ -0,0 +1,31 @@
+#![forbid(unsafe_code)]
+
+//! Game datasets used to resolve numeric ids in processed mail reports.
+//!
+//! The YAML files in the repository `datasets/` directory are embedded at
+//! compile time (or loaded from disk) and exposed as typed id lookups with
+//! localized names.
+
+mod dataset;
+mod enrich;
+mod error;
+mod import;
+mod locale;
+mod types;
+mod validate;
+
+pub use dataset::Datasets;
+pub use enrich::{enrich_battle, enrich_duelbattle2};
+pub use error::DatasetError;
+pub use import::{
+    Catalogs, ImportOptions, ImportReport, NameRule, NameTarget, NameUpdate, NewEntry, UpdateKind,
+    import_names, import_names_dir,
+};
+pub use locale::{Locale, LocalizedText};
+pub use types::{
+    Armament, Commander, CommanderRarity, CommanderSkill, EquipmentItem, Formation, Inscription,
+    InscriptionRarity, LootItem,
+};
+pub use validate::{
+    IssueKind, ValidationIssue, valid_equipment_attributes, validate_dir, validate_embedded,
+};
@@ -0,0 +1,182 @@
+//! Supported locales and localized text lookups.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::iter;
+
+use serde::{Deserialize, Serialize, Serializer};
+
+/// Game locales present in every dataset file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Locale {
+    Ar,
+    De,
+    En,
+    Es,
+    Fr,
+    Id,
+    It,
+    Ja,
+    Ko,
+    Ms,
+    Pl,
+    Pt,
+    Ru,
+    Th,
+    Tr,
+    Vi,
+    ZhCn,
+    ZhTw,
+}
+
+impl Locale {
+    /// Every supported locale, in dataset file order.
+    pub const ALL: [Locale; 18] = [
+        Locale::Ar,
+        Locale::De,
+        Locale::En,
+        Locale::Es,
+        Locale::Fr,
+        Locale::Id,
+        Locale::It,
+        Locale::Ja,
+        Locale::Ko,
+        Locale::Ms,
+        Locale::Pl,
+        Locale::Pt,
+        Locale::Ru,
+        Locale::Th,
+        Locale::Tr,
+        Locale::Vi,
+        Locale::ZhCn,
+        Locale::ZhTw,
+    ];
+
+    /// The locale used when a requested translation is missing.
+    pub const FALLBACK: Locale = Locale::En;
+
+    /// Parse a dataset locale code such as `en` or `zh_CN`.
+    ///
+    /// Hyphenated codes (`zh-CN`) and lowercase region codes are also accepted.
+    pub fn from_code(code: &str) -> Option<Self> {
+        let normalized = code.trim().replace('-', "_").to_ascii_lowercase();
+        Locale::ALL
+            .into_iter()
+            .find(|locale| locale.code().to_ascii_lowercase() == normalized)
+    }
+
+    /// Locales to try, in order, when reading text for this locale.
+    ///
+    /// Chinese variants fall back to each other before English.
+    pub fn fallbacks(self) -> impl Iterator<Item = Locale> {
+        let sibling = match self {
+            Locale::ZhCn => Some(Locale::ZhTw),
+            Locale::ZhTw => Some(Locale::ZhCn),
+            _ => None,
+        };
+        iter::once(self)
+            .chain(sibling)
+            .chain((self != Locale::FALLBACK).then_some(Locale::FALLBACK))
+    }
+
+    /// The locale code used as a key in dataset files.
+    pub fn code(self) -> &'static str {
+        match self {
+            Locale::Ar => "ar",
+            Locale::De => "de",
+            Locale::En => "en",
+            Locale::Es => "es",
+            Locale::Fr => "fr",
+            Locale::Id => "id",
+            Locale::It => "it",
+            Locale::Ja => "ja",
+            Locale::Ko => "ko",
+            Locale::Ms => "ms",
+            Locale::Pl => "pl",
+            Locale::Pt => "pt",
+            Locale::Ru => "ru",
+            Locale::Th => "th",
+            Locale::Tr => "tr",
+            Locale::Vi => "vi",
+            Locale::ZhCn => "zh_CN",
+            Locale::ZhTw => "zh_TW",
+        }
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+impl Serialize for Locale {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.code())
+    }
+}
+
+/// Text translated into one or more locales, keyed by locale code.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(transparent)]
+pub struct LocalizedText {
+    values: BTreeMap<String, String>,
+}
+
+impl LocalizedText {
+    /// Read the text for a locale, following [`Locale::fallbacks`] when missing or empty.
+    pub fn get(&self, locale: Locale) -> Option<&str> {
+        locale
+            .fallbacks()
+            .find_map(|candidate| self.get_exact(candidate))
+    }
+
+    /// Iterate raw `(locale code, text)` pairs in code order, including unknown codes.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.values
+            .iter()
+            .map(|(code, text)| (code.as_str(), text.as_str()))
+    }
+
+    /// Read the text for a locale without falling back.
+    pub fn get_exact(&self, locale: Locale) -> Option<&str> {
+        self.values
+            .get(locale.code())
+            .map(String::as_str)
+            .filter(|text| !text.is_empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_code_accepts_dataset_and_hyphenated_codes() {
+        assert_eq!(Locale::from_code("zh_CN"), Some(Locale::ZhCn));
+        assert_eq!(Locale::from_code("zh-tw"), Some(Locale::ZhTw));
+        assert_eq!(Locale::from_code("EN"), Some(Locale::En));
+        assert_eq!(Locale::from_code("xx"), None);
+    }
+
+    #[test]
+    fn localized_text_falls_back_to_english() {
+        let text: LocalizedText =
+            serde_yaml::from_str("en: \"Wedge\"\nde: \"\"\nfr: \"Coin\"").unwrap();
+        assert_eq!(text.get(Locale::Fr), Some("Coin"));
+        assert_eq!(text.get(Locale::De), Some("Wedge"));
+        assert_eq!(text.get_exact(Locale::De), None);
+    }
+
+    #[test]
+    fn localized_text_falls_back_between_chinese_variants() {
+        let text: LocalizedText =
+            serde_yaml::from_str("en: \"Wedge\"\nzh_CN: \"锋矢阵\"\nzh_TW: \"\"").unwrap();
+        assert_eq!(text.get(Locale::ZhTw), Some("锋矢阵"));
+        assert_eq!(
+            Locale::ZhTw.fallbacks().collect::<Vec<_>>(),
+            [Locale::ZhTw, Locale::ZhCn, Locale::En]
+        );
+        assert_eq!(Locale::En.fallbacks().collect::<Vec<_>>(), [Locale::En]);
+    }
+}
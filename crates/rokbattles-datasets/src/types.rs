@@ -0,0 +1,95 @@
+//! Typed dataset entries.
+
+use serde::Deserialize;
+
+use crate::LocalizedText;
+
+/// A commander entry from `commanders.yaml`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Commander {
+    /// The localized commander name.
+    pub name: LocalizedText,
+    /// The localized commander title.
+    #[serde(default)]
+    pub nickname: LocalizedText,
+    /// Whether the commander is a prime variant.
+    #[serde(default)]
+    pub prime: Option<bool>,
+    /// The commander rarity, if documented.
+    #[serde(default)]
+    pub rarity: Option<CommanderRarity>,
+    /// Skills in slot order.
+    #[serde(default)]
+    pub skills: Vec<CommanderSkill>,
+}
+
+/// Commander rarity tiers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CommanderRarity {
+    Advanced,
+    Elite,
+    Epic,
+    Legendary,
+}
+
+/// A commander skill slot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub struct CommanderSkill {
+    /// The skill slot (1-based).
+    pub slot: u64,
+    /// The skill id.
+    pub id: u64,
+    /// The skill id used once the commander is expertised, if it changes.
+    #[serde(default, rename = "expertId")]
+    pub expert_id: Option<u64>,
+}
+
+/// An armament stat (buff) entry from `armaments.yaml`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Armament {
+    /// The localized stat name.
+    pub name: LocalizedText,
+    /// Whether stat values are fractions to display as percentages.
+    #[serde(default)]
+    pub percent: bool,
+}
+
+/// An equipment item entry from `equipment.yaml`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct EquipmentItem {
+    /// The localized item name.
+    pub name: LocalizedText,
+}
+
+/// A formation entry from `formations.yaml`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Formation {
+    /// The localized formation name.
+    pub name: LocalizedText,
+}
+
+/// An inscription entry from `inscriptions.yaml`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Inscription {
+    /// The localized inscription name.
+    pub name: LocalizedText,
+    /// The inscription rarity.
+    pub rarity: InscriptionRarity,
+}
+
+/// Inscription rarity tiers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InscriptionRarity {
+    Common,
+    Rare,
+    Special,
+}
+
+/// A loot entry from `loot.yaml`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct LootItem {
+    /// The localized loot name.
+    pub name: LocalizedText,
+}
@@ -0,0 +1,463 @@
+//! Consistency checks for hand-edited dataset files.
+
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::dataset::{
+    ArmamentsFile, CommandersFile, EMBEDDED, EquipmentFile, FormationsFile, InscriptionsFile,
+    LootFile, parse, read_file,
+};
+use crate::{DatasetError, Datasets, InscriptionRarity, Locale, LocalizedText};
+
+// Documented id ranges:
+// - inscriptions: the hundreds digit is the armament category (1XX scroll,
+//   2XX instrument, 3XX flag, 4XX emblem). Ids below 1000 are common; longer
+//   ids end in 1 for special and 2 for rare.
+// - equipment attributes (`{slot:id[_craft]:attributes}` examples in
+//   equipment.yaml): YX where Y is 0 (none), 1 infantry, 2 archer, 3 cavalry,
+//   4 integration, 5 leadership or 16 engineering, and X is the iconic level.
+
+/// Categories of dataset problems.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueKind {
+    /// The file is not valid YAML for its expected shape.
+    Parse,
+    /// A key appears twice under the same parent (ids, locales, skill slots).
+    DuplicateId,
+    /// A name block lacks a supported locale.
+    MissingLocale,
+    /// A name block has a locale code that is not supported.
+    UnknownLocale,
+    /// A name block has an empty translation.
+    EmptyName,
+    /// An id falls outside its documented range.
+    IdOutOfRange,
+    /// A declared rarity does not match the rarity encoded in the id.
+    RarityMismatch,
+    /// An equipment attribute code does not follow the YX scheme.
+    InvalidEquipmentAttribute,
+}
+
+/// A single dataset problem.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ValidationIssue {
+    /// The dataset file name.
+    pub file: &'static str,
+    /// The problem category.
+    pub kind: IssueKind,
+    /// Dotted path to the offending entry (for example `inscriptions.101.name.de`).
+    pub path: String,
+    /// The 1-based line number, when known.
+    pub line: Option<usize>,
+    /// A human-readable description.
+    pub message: String,
+}
+
+/// Validate the datasets embedded at compile time.
+pub fn validate_embedded() -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+    for (file, text) in Datasets::FILES.into_iter().zip(EMBEDDED) {
+        validate_file(file, text, &mut issues);
+    }
+    issues
+}
+
+/// Validate every dataset file in a directory.
+pub fn validate_dir(dir: &Path) -> Result<Vec<ValidationIssue>, DatasetError> {
+    let mut issues = Vec::new();
+    for file in Datasets::FILES {
+        let text = read_file(dir, file)?;
+        validate_file(file, &text, &mut issues);
+    }
+    Ok(issues)
+}
+
+fn validate_file(file: &'static str, text: &str, issues: &mut Vec<ValidationIssue>) {
+    let mut checker = Checker { file, issues };
+    checker.duplicate_keys(text);
+    if file == "equipment.yaml" {
+        checker.equipment_attribute_examples(text);
+    }
+    if let Err(error) = checker.typed(text) {
+        let DatasetError::Parse { source, .. } = &error else {
+            return;
+        };
+        let line = source.location().map(|location| location.line());
+        checker.push(IssueKind::Parse, String::new(), line, error.to_string());
+    }
+}
+
+struct Checker<'a> {
+    file: &'static str,
+    issues: &'a mut Vec<ValidationIssue>,
+}
+
+/// A mapping level seen while scanning for duplicate keys.
+struct Frame {
+    indent: usize,
+    keys: BTreeSet<String>,
+    last: String,
+}
+
+impl Checker<'_> {
+    fn push(&mut self, kind: IssueKind, path: String, line: Option<usize>, message: String) {
+        self.issues.push(ValidationIssue {
+            file: self.file,
+            kind,
+            path,
+            line,
+            message,
+        });
+    }
+
+    /// Report keys repeated under the same parent.
+    ///
+    /// YAML parsers keep the last duplicate silently, so this scans the block
+    /// layout the dataset files use instead of the parsed value.
+    fn duplicate_keys(&mut self, text: &str) {
+        let mut frames: Vec<Frame> = Vec::new();
+        for (index, raw) in text.lines().enumerate() {
+            let trimmed = raw.trim_start();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+            let mut indent = raw.len() - trimmed.len();
+            let mut entry = trimmed;
+            let list_item = entry.starts_with("- ");
+            if list_item {
+                entry = entry[2..].trim_start();
+                indent = raw.len() - entry.len();
+            }
+            let Some((key, _)) = entry.split_once(':') else {
+                continue;
+            };
+            let key = key.trim().to_string();
+
+            while frames
+                .last()
+                .is_some_and(|frame| frame.indent > indent || (list_item && frame.indent == indent))
+            {
+                frames.pop();
+            }
+            match frames.last_mut() {
+                Some(frame) if frame.indent == indent => {
+                    if !frame.keys.insert(key.clone()) {
+                        let path = frames
+                            .iter()
+                            .take(frames.len() - 1)
+                            .map(|frame| frame.last.as_str())
+                            .chain([key.as_str()])
+                            .collect::<Vec<_>>()
+                            .join(".");
+                        self.push(
+                            IssueKind::DuplicateId,
+                            path,
+                            Some(index + 1),
+                            format!("duplicate key `{key}`"),
+                        );
+                    }
+                    if let Some(frame) = frames.last_mut() {
+                        frame.last = key;
+                    }
+                }
+                _ => frames.push(Frame {
+                    indent,
+                    keys: BTreeSet::from([key.clone()]),
+                    last: key,
+                }),
+            }
+        }
+    }
+
+    /// Check `{slot:id[_craft]:attributes}` examples documented in comments.
+    fn equipment_attribute_examples(&mut self, text: &str) {
+        for (index, raw) in text.lines().enumerate() {
+            let Some(comment) = raw.trim_start().strip_prefix('#') else {
+                continue;
+            };
+            let Some(start) = comment.find('{') else {
+                continue;
+            };
+            let Some(end) = comment[start..].find('}') else {
+                continue;
+            };
+            let example = &comment[start + 1..start + end];
+            for entry in example.split(',') {
+                let Some(code) = entry.rsplit(':').next() else {
+                    continue;
+                };
+                // Skip the format description itself (`attributes`).
+                let Ok(code) = code.trim().parse::<u64>() else {
+                    continue;
+                };
+                if !valid_equipment_attributes(code) {
+                    self.push(
+                        IssueKind::InvalidEquipmentAttribute,
+                        "equipment".to_string(),
+                        Some(index + 1),
+                        format!("attribute code {code} does not match the YX scheme"),
+                    );
+                }
+            }
+        }
+    }
+
+    fn typed(&mut self, text: &str) -> Result<(), DatasetError> {
+        match self.file {
+            "armaments.yaml" => {
+                let parsed: ArmamentsFile = parse(self.file, text)?;
+                for (id, entry) in &parsed.armaments {
+                    self.names(&format!("armaments.{id}.name"), &entry.name);
+                }
+            }
+            "commanders.yaml" => {
+                let parsed: CommandersFile = parse(self.file, text)?;
+                for (id, entry) in &parsed.commanders {
+                    self.names(&format!("commanders.{id}.name"), &entry.name);
+                    self.names(&format!("commanders.{id}.nickname"), &entry.nickname);
+                    let mut slots = BTreeSet::new();
+                    for skill in &entry.skills {
+                        if !slots.insert(skill.slot) {
+                            self.push(
+                                IssueKind::DuplicateId,
+                                format!("commanders.{id}.skills"),
+                                None,
+                                format!("duplicate skill slot {}", skill.slot),
+                            );
+                        }
+                    }
+                }
+            }
+            "equipment.yaml" => {
+                let parsed: EquipmentFile = parse(self.file, text)?;
+                for (slot, name) in &parsed.equipment.slot {
+                    self.names(&format!("equipment.slot.{slot}"), name);
+                }
+                for (id, entry) in &parsed.equipment.item {
+                    self.names(&format!("equipment.item.{id}.name"), &entry.name);
+                }
+            }
+            "formations.yaml" => {
+                let parsed: FormationsFile = parse(self.file, text)?;
+                for (id, entry) in &parsed.formations {
+                    self.names(&format!("formations.{id}.name"), &entry.name);
+                }
+            }
+            "inscriptions.yaml" => {
+                let parsed: InscriptionsFile = parse(self.file, text)?;
+                for (id, entry) in &parsed.inscriptions {
+                    self.names(&format!("inscriptions.{id}.name"), &entry.name);
+                    self.inscription(*id, entry.rarity);
+                }
+            }
+            "loot.yaml" => {
+                let parsed: LootFile = parse(self.file, text)?;
+                for (loot_type, entries) in &parsed.loot {
+                    for (sub_type, entry) in entries {
+                        self.names(&format!("loot.{loot_type}.{sub_type}.name"), &entry.name);
+                    }
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn names(&mut self, path: &str, text: &LocalizedText) {
+        for locale in Locale::ALL {
+            if text.iter().all(|(code, _)| code != locale.code()) {
+                self.push(
+                    IssueKind::MissingLocale,
+                    format!("{path}.{locale}"),
+                    None,
+                    format!("missing `{locale}` translation"),
+                );
+            }
+        }
+        for (code, value) in text.iter() {
+            if Locale::ALL.iter().all(|locale| locale.code() != code) {
+                self.push(
+                    IssueKind::UnknownLocale,
+                    format!("{path}.{code}"),
+                    None,
+                    format!("unsupported locale code `{code}`"),
+                );
+            } else if value.trim().is_empty() {
+                self.push(
+                    IssueKind::EmptyName,
+                    format!("{path}.{code}"),
+                    None,
+                    format!("empty `{code}` translation"),
+                );
+            }
+        }
+    }
+
+    fn inscription(&mut self, id: u64, rarity: InscriptionRarity) {
+        let path = format!("inscriptions.{id}");
+        let category = (id / 100) % 10;
+        if !(1..=4).contains(&category) {
+            self.push(
+                IssueKind::IdOutOfRange,
+                path.clone(),
+                None,
+                format!("category digit {category} is outside 1XX-4XX"),
+            );
+        }
+        let expected = match id {
+            0..1000 => Some(InscriptionRarity::Common),
+            _ if id % 10 == 1 => Some(InscriptionRarity::Special),
+            _ if id % 10 == 2 => Some(InscriptionRarity::Rare),
+            _ => None,
+        };
+        match expected {
+            Some(expected) if expected != rarity => self.push(
+                IssueKind::RarityMismatch,
+                path,
+                None,
+                format!("declared {rarity:?} but id encodes {expected:?}"),
+            ),
+            Some(_) => {}
+            None => self.push(
+                IssueKind::IdOutOfRange,
+                path,
+                None,
+                "id does not end in 1 (special) or 2 (rare)".to_string(),
+            ),
+        }
+    }
+}
+
+/// Whether an equipment attribute code follows the documented YX scheme.
+pub fn valid_equipment_attributes(code: u64) -> bool {
+    matches!(code / 10, 0..=5 | 16)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn issues(file: &'static str, text: &str) -> Vec<ValidationIssue> {
+        let mut issues = Vec::new();
+        validate_file(file, text, &mut issues);
+        issues
+    }
+
+    fn full_name(indent: &str, skip: Option<Locale>) -> String {
+        Locale::ALL
+            .into_iter()
+            .filter(|locale| Some(*locale) != skip)
+            .map(|locale| format!("{indent}{locale}: \"x\"\n"))
+            .collect()
+    }
+
+    #[test]
+    fn embedded_datasets_have_no_structural_issues() {
+        let issues = validate_embedded();
+        let structural: Vec<_> = issues
+            .iter()
+            .filter(|issue| {
+                matches!(
+                    issue.kind,
+                    IssueKind::Parse | IssueKind::InvalidEquipmentAttribute
+                )
+            })
+            .collect();
+        assert!(structural.is_empty(), "{structural:?}");
+    }
+
+    #[test]
+    fn validate_file_reports_duplicate_ids() {
+        let name = full_name("      ", None);
+        let text = format!("formations:\n  1:\n    name:\n{name}\n  1:\n    name:\n{name}");
+        let issues = issues("formations.yaml", &text);
+        let duplicates: Vec<_> = issues
+            .iter()
+            .filter(|issue| issue.kind == IssueKind::DuplicateId)
+            .collect();
+        assert_eq!(duplicates.len(), 1);
+        assert_eq!(duplicates[0].path, "formations.1");
+        assert_eq!(duplicates[0].line, Some(23));
+    }
+
+    #[test]
+    fn validate_file_reports_locale_problems() {
+        let name = full_name("      ", Some(Locale::De)).replace("fr: \"x\"", "fr: \"\"");
+        let text = format!("formations:\n  1:\n    name:\n{name}      xx: \"y\"\n");
+        let kinds: Vec<_> = issues("formations.yaml", &text)
+            .into_iter()
+            .map(|issue| (issue.kind, issue.path))
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                (IssueKind::MissingLocale, "formations.1.name.de".to_string()),
+                (IssueKind::EmptyName, "formations.1.name.fr".to_string()),
+                (IssueKind::UnknownLocale, "formations.1.name.xx".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn validate_file_checks_inscription_ranges_and_rarity() {
+        let name = full_name("      ", None);
+        let text = format!(
+            "inscriptions:\n  101:\n    name:\n{name}    rarity: rare\n  \
+             501:\n    name:\n{name}    rarity: common\n  \
+             1103:\n    name:\n{name}    rarity: special\n"
+        );
+        let kinds: Vec<_> = issues("inscriptions.yaml", &text)
+            .into_iter()
+            .map(|issue| (issue.kind, issue.path))
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                (IssueKind::RarityMismatch, "inscriptions.101".to_string()),
+                (IssueKind::IdOutOfRange, "inscriptions.501".to_string()),
+                (IssueKind::IdOutOfRange, "inscriptions.1103".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn validate_file_checks_commander_skill_slots() {
+        let name = full_name("      ", None);
+        let text = format!(
+            "commanders:\n  1:\n    name:\n{name}    nickname:\n{name}    skills:\n      \
+             - slot: 1\n        id: 22\n      - slot: 1\n        id: 23\n"
+        );
+        let issues = issues("commanders.yaml", &text);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].kind, IssueKind::DuplicateId);
+        assert_eq!(issues[0].path, "commanders.1.skills");
+    }
+
+    #[test]
+    fn validate_file_checks_equipment_attribute_examples() {
+        let text = "equipment:\n  # example: {2:20004_379:32,1:20001:67}\n  slot: {}\n  item: {}\n";
+        let issues = issues("equipment.yaml", text);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].kind, IssueKind::InvalidEquipmentAttribute);
+        assert_eq!(issues[0].line, Some(2));
+    }
+
+    #[test]
+    fn valid_equipment_attributes_follows_yx_scheme() {
+        assert!(valid_equipment_attributes(0));
+        assert!(valid_equipment_attributes(32));
+        assert!(valid_equipment_attributes(160));
+        assert!(!valid_equipment_attributes(67));
+        assert!(!valid_equipment_attributes(100));
+    }
+
+    #[test]
+    fn validate_file_reports_parse_errors() {
+        let issues = issues("loot.yaml", "loot: [");
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].kind, IssueKind::Parse);
+    }
+}
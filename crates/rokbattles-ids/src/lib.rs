@@ -0,0 +1,180 @@
+#![forbid(unsafe_code)]
+
+//! Typed game id mappings shared by mail processors.
+//!
+//! Each enum maps a numeric id found in mail reports to a known variant and
+//! keeps ids that are not documented yet in an `Unknown` variant, so raw ids
+//! always survive the round trip.
+
+/// Define an id enum with `from_id`, `id` and `label` conversions.
+macro_rules! id_enum {
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident {
+            $(
+                $(#[$variant_meta:meta])*
+                $variant:ident = $id:literal => $label:literal,
+            )+
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $(
+                $(#[$variant_meta])*
+                $variant,
+            )+
+            /// An id without a documented mapping.
+            Unknown(u64),
+        }
+
+        impl $name {
+            /// Resolve a raw id, falling back to `Unknown`.
+            pub fn from_id(id: u64) -> Self {
+                match id {
+                    $($id => Self::$variant,)+
+                    other => Self::Unknown(other),
+                }
+            }
+
+            /// The raw id as it appears in mail reports.
+            pub fn id(self) -> u64 {
+                match self {
+                    $(Self::$variant => $id,)+
+                    Self::Unknown(id) => id,
+                }
+            }
+
+            /// A stable snake_case label, `"unknown"` for unmapped ids.
+            pub fn label(self) -> &'static str {
+                match self {
+                    $(Self::$variant => $label,)+
+                    Self::Unknown(_) => "unknown",
+                }
+            }
+        }
+
+        impl From<u64> for $name {
+            fn from(id: u64) -> Self {
+                Self::from_id(id)
+            }
+        }
+    };
+}
+
+id_enum! {
+    /// Game client identified by the `app_id` prefix of `AppUid`.
+    pub enum AppClient {
+        /// International client.
+        International = 2_104_267 => "international",
+        /// Chinese client.
+        Chinese = 3_724_753 => "chinese",
+        /// Vietnamese client (Gamota).
+        Vietnamese = 6_626_468 => "vietnamese",
+        /// Korean client.
+        Korean = 8_518_744 => "korean",
+        /// Japanese client.
+        Japanese = 8_529_460 => "japanese",
+        /// Chinese client (Huawei).
+        ChineseHuawei = 9_105_753 => "chinese_huawei",
+        /// Chinese client (Taiwan).
+        ChineseTaiwan = 9_602_340 => "chinese_taiwan",
+    }
+}
+
+id_enum! {
+    /// Alliance building a player fights from or against (`AbT`).
+    pub enum AllianceBuilding {
+        /// Alliance flag.
+        Flag = 1 => "flag",
+        /// Alliance stronghold.
+        Stronghold = 3 => "stronghold",
+        /// Horse fort (Troy KvK).
+        HorseFort = 11 => "horse_fort",
+    }
+}
+
+id_enum! {
+    /// Map structure a player fights at (`ShId`).
+    pub enum Structure {
+        /// Shrine of War (Ark of Osiris).
+        ShrineOfWar = 22 => "shrine_of_war",
+        /// Shrine of Life (Ark of Osiris).
+        ShrineOfLife = 23 => "shrine_of_life",
+        /// Sky Altar (Ark of Osiris).
+        SkyAltar = 24 => "sky_altar",
+        /// An Ark of Osiris structure that has not been identified yet.
+        ArkUnidentified = 25 => "ark_unidentified",
+        /// Outpost of Iset (Ark of Osiris).
+        OutpostOfIset = 26 => "outpost_of_iset",
+        /// Great Ziggurat (KvK).
+        GreatZiggurat = 38 => "great_ziggurat",
+        /// Level 7 pass (KvK).
+        Level7Pass = 51 => "level_7_pass",
+        /// Obelisk (Ark of Osiris).
+        Obelisk = 109 => "obelisk",
+    }
+}
+
+impl Structure {
+    /// Whether the structure is an Ark of Osiris objective.
+    pub fn is_ark(self) -> bool {
+        matches!(
+            self,
+            Self::ShrineOfWar
+                | Self::ShrineOfLife
+                | Self::SkyAltar
+                | Self::ArkUnidentified
+                | Self::OutpostOfIset
+                | Self::Obelisk
+        )
+    }
+
+    /// Whether the structure is a KvK objective.
+    pub fn is_kvk(self) -> bool {
+        matches!(self, Self::GreatZiggurat | Self::Level7Pass)
+    }
+}
+
+id_enum! {
+    /// Bar canyon boss (`npcType`).
+    pub enum NpcBoss {
+        /// Miser Khaolak.
+        MiserKhaolak = 102_000_063 => "miser_khaolak",
+        /// Ironhand Baulur.
+        IronhandBaulur = 102_000_055 => "ironhand_baulur",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_ids_resolve_and_round_trip() {
+        assert_eq!(AppClient::from_id(8_518_744), AppClient::Korean);
+        assert_eq!(AppClient::Korean.id(), 8_518_744);
+        assert_eq!(AppClient::Korean.label(), "korean");
+        assert_eq!(AllianceBuilding::from(11), AllianceBuilding::HorseFort);
+        assert_eq!(Structure::from_id(51).label(), "level_7_pass");
+        assert_eq!(NpcBoss::from_id(102_000_055), NpcBoss::IronhandBaulur);
+    }
+
+    #[test]
+    fn unknown_ids_keep_the_raw_value() {
+        let structure = Structure::from_id(52);
+        assert_eq!(structure, Structure::Unknown(52));
+        assert_eq!(structure.id(), 52);
+        assert_eq!(structure.label(), "unknown");
+        assert!(!structure.is_ark());
+        assert!(!structure.is_kvk());
+    }
+
+    #[test]
+    fn structure_groups_follow_game_modes() {
+        assert!(Structure::ArkUnidentified.is_ark());
+        assert!(Structure::Obelisk.is_ark());
+        assert!(!Structure::Obelisk.is_kvk());
+        assert!(Structure::GreatZiggurat.is_kvk());
+    }
+}
@@ -9,7 +9,11 @@ pub struct Config {
     pub mongo_uri: String,
     pub batch_size: i64,
     pub concurrency: usize,
+    pub extractor_workers: usize,
     pub idle_sleep: Duration,
+    /// How long the processor lease is held without renewal. Must exceed the
+    /// time one batch takes.
+    pub lease_ttl: Duration,
 }
 
 /// Errors returned when configuration is missing or invalid.
@@ -35,21 +39,39 @@ impl Config {
             env::var("PROCESSOR_CONCURRENCY").ok(),
             8,
         )?;
+        let extractor_workers = parse_usize(
+            "PROCESSOR_EXTRACTOR_WORKERS",
+            env::var("PROCESSOR_EXTRACTOR_WORKERS").ok(),
+            default_extractor_workers(concurrency),
+        )?;
         let idle_sleep = parse_duration_secs(
             "PROCESSOR_IDLE_SLEEP_SECS",
             env::var("PROCESSOR_IDLE_SLEEP_SECS").ok(),
             15,
         )?;
+        let lease_ttl = parse_duration_secs(
+            "PROCESSOR_LEASE_SECS",
+            env::var("PROCESSOR_LEASE_SECS").ok(),
+            120,
+        )?;
 
         Ok(Self {
             mongo_uri,
             batch_size,
             concurrency,
+            extractor_workers,
             idle_sleep,
+            lease_ttl,
         })
     }
 }
 
+/// Split available cores across concurrently processed mails.
+fn default_extractor_workers(concurrency: usize) -> usize {
+    let cores = std::thread::available_parallelism().map_or(1, usize::from);
+    (cores / concurrency.max(1)).max(1)
+}
+
 fn required_env(key: &'static str) -> Result<String, ConfigError> {
     env::var(key).map_err(|_| ConfigError::Missing { key })
 }
@@ -130,6 +152,12 @@ mod tests {
         assert!(parse_usize("TEST", Some("0".into()), 3).is_err());
     }
 
+    #[test]
+    fn default_extractor_workers_is_at_least_one() {
+        assert!(default_extractor_workers(1) >= 1);
+        assert_eq!(default_extractor_workers(usize::MAX), 1);
+    }
+
     #[test]
     fn parse_duration_secs_uses_default() {
         let duration = parse_duration_secs("TEST", None, 5).unwrap();
@@ -0,0 +1,670 @@
+//! Correlation of Battle reports that describe the same engagement.
+//!
+//! Every processed Battle report becomes a [`Perspective`]: the sender, the
+//! attacks it saw and their wall-clock windows. Two perspectives belong to
+//! the same battle when their windows overlap and they share a tracking key,
+//! an attack id on an overlapping attack, or attack positions within
+//! [`POSITION_TOLERANCE`]. Matching perspectives are stored together in the
+//! `battles` collection with a merged view of every side; [`plan_merge`]
+//! decides which stored battles a new report joins.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde_json::{Map, Value, json};
+
+/// Seconds of slack when comparing windows from different reports.
+pub const TIME_TOLERANCE_SECS: u64 = 5;
+/// Maximum map distance between attack positions of the same engagement.
+pub const POSITION_TOLERANCE: f64 = 20.0;
+
+/// The correlation-relevant part of one processed Battle report.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Perspective {
+    pub mail_id: String,
+    pub mail_time: Option<u64>,
+    pub start_time: u64,
+    pub end_time: u64,
+    pub sender: Value,
+    pub tracking_key: Option<String>,
+    pub summary: Value,
+    pub attacks: Vec<AttackRef>,
+}
+
+/// One attack as seen from a perspective, with times in Unix seconds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AttackRef {
+    pub attack_id: String,
+    pub tracking_key: Option<String>,
+    pub x: Option<f64>,
+    pub y: Option<f64>,
+    pub start_time: u64,
+    pub end_time: u64,
+    pub opponent: Value,
+}
+
+impl Perspective {
+    /// Build a perspective from processed Battle output.
+    ///
+    /// Returns `None` when the report lacks a mail id or timeline.
+    pub fn from_processed(processed: &Value) -> Option<Self> {
+        let mail_id = processed
+            .pointer("/metadata/mail_id")?
+            .as_str()?
+            .to_string();
+        let timeline = processed.get("timeline")?;
+        let start_time = timeline.get("start_timestamp")?.as_u64()?;
+        let end_time = timeline.get("end_timestamp")?.as_u64()?;
+        let start_tick = timeline.get("start_tick")?.as_u64()?;
+        let tick_time = |tick: Option<u64>| {
+            tick.map(|tick| start_time + tick.saturating_sub(start_tick))
+                .unwrap_or(start_time)
+        };
+
+        let attacks = processed
+            .get("opponents")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|opponent| {
+                let attack = opponent.get("attack")?;
+                Some(AttackRef {
+                    attack_id: attack.get("id")?.as_str()?.to_string(),
+                    tracking_key: tracking_key(opponent),
+                    x: attack.get("x").and_then(Value::as_f64),
+                    y: attack.get("y").and_then(Value::as_f64),
+                    start_time: tick_time(opponent.get("start_tick").and_then(Value::as_u64)),
+                    end_time: tick_time(opponent.get("end_tick").and_then(Value::as_u64)),
+                    opponent: player_ref(opponent),
+                })
+            })
+            .collect();
+
+        let sender = processed.get("sender").unwrap_or(&Value::Null);
+        Some(Self {
+            mail_id,
+            mail_time: processed
+                .pointer("/metadata/mail_time")
+                .and_then(Value::as_u64),
+            start_time,
+            end_time: end_time.max(start_time),
+            tracking_key: tracking_key(sender),
+            sender: player_ref(sender),
+            summary: processed.get("summary").cloned().unwrap_or(Value::Null),
+            attacks,
+        })
+    }
+
+    /// Read a perspective stored by [`Perspective::to_json`].
+    pub fn from_json(value: &Value) -> Option<Self> {
+        let attacks = value
+            .get("attacks")?
+            .as_array()?
+            .iter()
+            .map(|attack| {
+                Some(AttackRef {
+                    attack_id: attack.get("attack_id")?.as_str()?.to_string(),
+                    tracking_key: optional_string(attack.get("tracking_key")),
+                    x: attack.get("x").and_then(Value::as_f64),
+                    y: attack.get("y").and_then(Value::as_f64),
+                    start_time: attack.get("start_time")?.as_u64()?,
+                    end_time: attack.get("end_time")?.as_u64()?,
+                    opponent: attack.get("opponent").cloned().unwrap_or(Value::Null),
+                })
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        Some(Self {
+            mail_id: value.get("mail_id")?.as_str()?.to_string(),
+            mail_time: value.get("mail_time").and_then(Value::as_u64),
+            start_time: value.get("start_time")?.as_u64()?,
+            end_time: value.get("end_time")?.as_u64()?,
+            sender: value.get("sender").cloned().unwrap_or(Value::Null),
+            tracking_key: optional_string(value.get("tracking_key")),
+            summary: value.get("summary").cloned().unwrap_or(Value::Null),
+            attacks,
+        })
+    }
+
+    /// Serialize the perspective for storage on a battle document.
+    pub fn to_json(&self) -> Value {
+        let attacks = self
+            .attacks
+            .iter()
+            .map(|attack| {
+                json!({
+                    "attack_id": attack.attack_id,
+                    "tracking_key": attack.tracking_key,
+                    "x": attack.x,
+                    "y": attack.y,
+                    "start_time": attack.start_time,
+                    "end_time": attack.end_time,
+                    "opponent": attack.opponent,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        json!({
+            "mail_id": self.mail_id,
+            "mail_time": self.mail_time,
+            "start_time": self.start_time,
+            "end_time": self.end_time,
+            "sender": self.sender,
+            "tracking_key": self.tracking_key,
+            "summary": self.summary,
+            "attacks": attacks,
+        })
+    }
+
+    /// Tracking keys of the sender and every opponent march.
+    fn tracking_keys(&self) -> impl Iterator<Item = &str> {
+        self.tracking_key
+            .iter()
+            .chain(
+                self.attacks
+                    .iter()
+                    .filter_map(|attack| attack.tracking_key.as_ref()),
+            )
+            .map(String::as_str)
+    }
+}
+
+/// Whether two perspectives describe the same engagement.
+pub fn same_engagement(a: &Perspective, b: &Perspective) -> bool {
+    if !windows_overlap((a.start_time, a.end_time), (b.start_time, b.end_time)) {
+        return false;
+    }
+
+    let keys = a.tracking_keys().collect::<BTreeSet<_>>();
+    if b.tracking_keys().any(|key| keys.contains(key)) {
+        return true;
+    }
+
+    a.attacks
+        .iter()
+        .any(|left| b.attacks.iter().any(|right| attacks_match(left, right)))
+}
+
+fn attacks_match(a: &AttackRef, b: &AttackRef) -> bool {
+    if !windows_overlap((a.start_time, a.end_time), (b.start_time, b.end_time)) {
+        return false;
+    }
+    if a.attack_id == b.attack_id {
+        return true;
+    }
+
+    match (a.x, a.y, b.x, b.y) {
+        (Some(ax), Some(ay), Some(bx), Some(by)) => (ax - bx).hypot(ay - by) <= POSITION_TOLERANCE,
+        _ => false,
+    }
+}
+
+fn windows_overlap(a: (u64, u64), b: (u64, u64)) -> bool {
+    a.0 <= b.1 + TIME_TOLERANCE_SECS && b.0 <= a.1 + TIME_TOLERANCE_SECS
+}
+
+/// A stored battle read back for correlation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredBattle<Id> {
+    pub id: Id,
+    /// Write counter used to detect concurrent updates.
+    pub version: i64,
+    pub perspectives: Vec<Perspective>,
+}
+
+impl<Id> StoredBattle<Id> {
+    /// Read the perspectives of a document built by [`battle_document`].
+    pub fn from_json(id: Id, version: i64, battle: &Value) -> Self {
+        let perspectives = battle
+            .get("perspectives")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(Perspective::from_json)
+            .collect();
+        Self {
+            id,
+            version,
+            perspectives,
+        }
+    }
+}
+
+/// How a report joins the stored battles.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergePlan<Id> {
+    /// The battle to replace and the version it was read at; `None` inserts.
+    pub keep: Option<(Id, i64)>,
+    /// Battles folded into `keep`, with the versions they were read at.
+    pub absorbed: Vec<(Id, i64)>,
+    /// Every perspective of the resulting battle.
+    pub perspectives: Vec<Perspective>,
+}
+
+/// Decide which stored battles a report merges with.
+///
+/// Every candidate that already holds the report or matches it is merged; the
+/// first one keeps its id and the rest are absorbed. A stored copy of the
+/// report is replaced by the new one, so reprocessing is idempotent.
+pub fn plan_merge<Id>(
+    perspective: &Perspective,
+    candidates: impl IntoIterator<Item = StoredBattle<Id>>,
+) -> MergePlan<Id> {
+    let mut plan = MergePlan {
+        keep: None,
+        absorbed: Vec::new(),
+        perspectives: vec![perspective.clone()],
+    };
+    for candidate in candidates {
+        let contains_mail = candidate
+            .perspectives
+            .iter()
+            .any(|stored| stored.mail_id == perspective.mail_id);
+        let stored = candidate
+            .perspectives
+            .into_iter()
+            .filter(|stored| stored.mail_id != perspective.mail_id)
+            .collect::<Vec<_>>();
+        if !contains_mail
+            && !stored
+                .iter()
+                .any(|stored| same_engagement(stored, perspective))
+        {
+            continue;
+        }
+
+        let battle = (candidate.id, candidate.version);
+        if plan.keep.is_none() {
+            plan.keep = Some(battle);
+        } else {
+            plan.absorbed.push(battle);
+        }
+        plan.perspectives.extend(stored);
+    }
+    plan
+}
+
+/// Build the stored `battles` document for a group of perspectives.
+///
+/// Perspectives are ordered by mail time, then mail id, so the document is
+/// stable regardless of the order reports arrive in.
+pub fn battle_document(perspectives: &[Perspective]) -> Value {
+    let mut perspectives = perspectives.iter().collect::<Vec<_>>();
+    perspectives.sort_by(|a, b| {
+        a.mail_time
+            .cmp(&b.mail_time)
+            .then_with(|| a.mail_id.cmp(&b.mail_id))
+    });
+    perspectives.dedup_by(|a, b| a.mail_id == b.mail_id);
+
+    let mail_ids = perspectives
+        .iter()
+        .map(|perspective| perspective.mail_id.as_str())
+        .collect::<BTreeSet<_>>();
+    let tracking_keys = perspectives
+        .iter()
+        .flat_map(|perspective| perspective.tracking_keys())
+        .collect::<BTreeSet<_>>();
+    let attack_ids = perspectives
+        .iter()
+        .flat_map(|perspective| &perspective.attacks)
+        .map(|attack| attack.attack_id.as_str())
+        .collect::<BTreeSet<_>>();
+
+    json!({
+        "mail_ids": mail_ids,
+        "tracking_keys": tracking_keys,
+        "attack_ids": attack_ids,
+        "start_time": perspectives.iter().map(|perspective| perspective.start_time).min(),
+        "end_time": perspectives.iter().map(|perspective| perspective.end_time).max(),
+        "perspectives": perspectives
+            .iter()
+            .map(|perspective| perspective.to_json())
+            .collect::<Vec<_>>(),
+        "merged": merged_view(&perspectives),
+    })
+}
+
+/// Combine perspectives into one list of sides and deduplicated attacks.
+fn merged_view(perspectives: &[&Perspective]) -> Value {
+    let mut sides = BTreeMap::<String, Map<String, Value>>::new();
+    let mut attacks = BTreeMap::<(String, String), Map<String, Value>>::new();
+
+    for perspective in perspectives {
+        let side = side_entry(&mut sides, &perspective.sender);
+        push_unique(side, "reported_by", &perspective.mail_id);
+        side.entry("summary").or_insert_with(|| {
+            perspective
+                .summary
+                .get("sender")
+                .cloned()
+                .unwrap_or(Value::Null)
+        });
+
+        for attack in &perspective.attacks {
+            push_unique(
+                side_entry(&mut sides, &attack.opponent),
+                "faced_in",
+                &perspective.mail_id,
+            );
+
+            let key = (attack.attack_id.clone(), player_key(&attack.opponent));
+            let entry = attacks.entry(key).or_insert_with(|| {
+                let mut entry = Map::new();
+                entry.insert(
+                    "attack_id".to_string(),
+                    Value::from(attack.attack_id.as_str()),
+                );
+                entry.insert("opponent".to_string(), attack.opponent.clone());
+                entry.insert("x".to_string(), json!(attack.x));
+                entry.insert("y".to_string(), json!(attack.y));
+                entry.insert("start_time".to_string(), Value::from(attack.start_time));
+                entry.insert("end_time".to_string(), Value::from(attack.end_time));
+                entry
+            });
+            widen(entry, "start_time", attack.start_time, u64::min);
+            widen(entry, "end_time", attack.end_time, u64::max);
+            if let Some(key) = &attack.tracking_key {
+                push_unique(entry, "tracking_keys", key);
+            }
+            push_unique(entry, "reported_by", &perspective.mail_id);
+        }
+    }
+
+    json!({
+        "reports": perspectives.len(),
+        "sides": sides.into_values().collect::<Vec<_>>(),
+        "attacks": attacks.into_values().collect::<Vec<_>>(),
+    })
+}
+
+fn side_entry<'a>(
+    sides: &'a mut BTreeMap<String, Map<String, Value>>,
+    player: &Value,
+) -> &'a mut Map<String, Value> {
+    sides.entry(player_key(player)).or_insert_with(|| {
+        let mut side = player.as_object().cloned().unwrap_or_default();
+        side.insert("reported_by".to_string(), json!([]));
+        side.insert("faced_in".to_string(), json!([]));
+        side
+    })
+}
+
+/// Key players by id, falling back to name for ids the game leaves at 0.
+fn player_key(player: &Value) -> String {
+    match player.get("player_id").and_then(Value::as_i64) {
+        Some(id) if id != 0 => id.to_string(),
+        _ => format!(
+            "name:{}",
+            player
+                .get("player_name")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+        ),
+    }
+}
+
+fn push_unique(object: &mut Map<String, Value>, field: &str, value: &str) {
+    let list = object
+        .entry(field)
+        .or_insert_with(|| json!([]))
+        .as_array_mut()
+        .expect("list fields are arrays");
+    if !list.iter().any(|existing| existing == value) {
+        list.push(Value::from(value));
+    }
+}
+
+fn widen(object: &mut Map<String, Value>, field: &str, value: u64, pick: fn(u64, u64) -> u64) {
+    let current = object.get(field).and_then(Value::as_u64).unwrap_or(value);
+    object.insert(field.to_string(), Value::from(pick(current, value)));
+}
+
+/// The identifying subset of a processed player entry.
+fn player_ref(player: &Value) -> Value {
+    json!({
+        "player_id": player.get("player_id"),
+        "player_name": player.get("player_name"),
+        "kingdom_id": player.get("kingdom_id"),
+        "alliance": player.get("alliance"),
+    })
+}
+
+fn tracking_key(player: &Value) -> Option<String> {
+    optional_string(player.get("tracking_key"))
+}
+
+fn optional_string(value: Option<&Value>) -> Option<String> {
+    value
+        .and_then(Value::as_str)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn processed(
+        mail_id: &str,
+        sender: (i64, &str),
+        start: u64,
+        attacks: &[(&str, i64, &str, f64, u64)],
+    ) -> Value {
+        let opponents = attacks
+            .iter()
+            .map(|(attack_id, player_id, tracking_key, x, offset)| {
+                json!({
+                    "player_id": player_id,
+                    "player_name": format!("P{player_id}"),
+                    "kingdom_id": 1804,
+                    "alliance": { "id": 1, "name": "A", "abbreviation": "A" },
+                    "tracking_key": tracking_key,
+                    "attack": { "id": attack_id, "x": x, "y": 100.0 },
+                    "start_tick": 1000 + offset,
+                    "end_tick": 1010 + offset,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        json!({
+            "metadata": { "mail_id": mail_id, "mail_time": start * 1000 },
+            "sender": {
+                "player_id": sender.0,
+                "player_name": format!("P{}", sender.0),
+                "kingdom_id": 1804,
+                "alliance": { "id": 2, "name": "B", "abbreviation": "B" },
+                "tracking_key": sender.1,
+            },
+            "summary": { "sender": { "kill_points": 10 }, "opponent": { "kill_points": 5 } },
+            "opponents": opponents,
+            "timeline": {
+                "start_timestamp": start,
+                "end_timestamp": start + 30,
+                "start_tick": 1000,
+            },
+        })
+    }
+
+    fn perspective(value: Value) -> Perspective {
+        Perspective::from_processed(&value).expect("perspective")
+    }
+
+    #[test]
+    fn perspective_reads_processed_battle_output() {
+        let perspective = perspective(processed(
+            "mail-1",
+            (1, "1_100_15"),
+            5000,
+            &[("77", 2, "", 10.0, 5)],
+        ));
+
+        assert_eq!(perspective.mail_id, "mail-1");
+        assert_eq!(perspective.tracking_key.as_deref(), Some("1_100_15"));
+        assert_eq!(perspective.attacks[0].tracking_key, None);
+        assert_eq!(perspective.attacks[0].start_time, 5005);
+        assert_eq!(perspective.attacks[0].end_time, 5015);
+        assert_eq!(perspective.attacks[0].opponent["player_id"], json!(2));
+        assert_eq!(
+            Perspective::from_json(&perspective.to_json()),
+            Some(perspective)
+        );
+        assert!(Perspective::from_processed(&json!({ "metadata": {} })).is_none());
+    }
+
+    #[test]
+    fn same_engagement_matches_tracking_keys_across_sides() {
+        let attacker = perspective(processed(
+            "a",
+            (1, "1_100_15"),
+            5000,
+            &[("77", 2, "2_90_3", 10.0, 0)],
+        ));
+        let defender = perspective(processed(
+            "b",
+            (2, "2_90_3"),
+            5004,
+            &[("88", 1, "", 900.0, 0)],
+        ));
+        let later = perspective(processed(
+            "c",
+            (2, "2_90_3"),
+            9000,
+            &[("88", 1, "", 900.0, 0)],
+        ));
+
+        assert!(same_engagement(&attacker, &defender));
+        assert!(!same_engagement(&attacker, &later));
+    }
+
+    #[test]
+    fn same_engagement_matches_attack_ids_and_positions() {
+        let base = perspective(processed("a", (1, ""), 5000, &[("77", 9, "", 10.0, 0)]));
+        let same_id = perspective(processed("b", (3, ""), 5002, &[("77", 9, "", 500.0, 0)]));
+        let nearby = perspective(processed("c", (4, ""), 5002, &[("99", 9, "", 25.0, 0)]));
+        let far = perspective(processed("d", (5, ""), 5002, &[("99", 9, "", 40.0, 0)]));
+        let same_id_later_attack =
+            perspective(processed("e", (6, ""), 5000, &[("77", 9, "", 10.0, 25)]));
+
+        assert!(same_engagement(&base, &same_id));
+        assert!(same_engagement(&base, &nearby));
+        assert!(!same_engagement(&base, &far));
+        assert!(!same_engagement(&base, &same_id_later_attack));
+    }
+
+    fn stored(id: u32, version: i64, perspectives: &[Perspective]) -> StoredBattle<u32> {
+        StoredBattle::from_json(id, version, &battle_document(perspectives))
+    }
+
+    #[test]
+    fn plan_merge_inserts_when_no_battle_matches() {
+        let report = perspective(processed("a", (1, ""), 5000, &[("77", 9, "", 10.0, 0)]));
+        let other = perspective(processed("b", (2, ""), 5000, &[("88", 8, "", 900.0, 0)]));
+
+        let plan = plan_merge(&report, [stored(1, 0, &[other])]);
+        assert_eq!(plan.keep, None);
+        assert!(plan.absorbed.is_empty());
+        assert_eq!(plan.perspectives, [report]);
+    }
+
+    #[test]
+    fn plan_merge_keeps_first_match_and_absorbs_the_rest() {
+        let left = perspective(processed("a", (1, ""), 5000, &[("77", 9, "", 10.0, 0)]));
+        let right = perspective(processed("b", (2, ""), 5000, &[("88", 8, "", 900.0, 0)]));
+        let unrelated = perspective(processed("c", (3, ""), 9000, &[("77", 9, "", 10.0, 0)]));
+        let bridge = perspective(processed(
+            "d",
+            (4, ""),
+            5001,
+            &[("77", 9, "", 10.0, 0), ("88", 8, "", 900.0, 0)],
+        ));
+
+        let plan = plan_merge(
+            &bridge,
+            [
+                stored(1, 3, std::slice::from_ref(&left)),
+                stored(2, 0, std::slice::from_ref(&unrelated)),
+                stored(3, 7, std::slice::from_ref(&right)),
+            ],
+        );
+        assert_eq!(plan.keep, Some((1, 3)));
+        assert_eq!(plan.absorbed, [(3, 7)]);
+        assert_eq!(plan.perspectives, [bridge, left, right]);
+
+        let document = battle_document(&plan.perspectives);
+        assert_eq!(document["mail_ids"], json!(["a", "b", "d"]));
+    }
+
+    #[test]
+    fn plan_merge_replaces_stored_copy_of_reprocessed_report() {
+        let first = perspective(processed("a", (1, ""), 5000, &[("77", 9, "", 10.0, 0)]));
+        let second = perspective(processed("b", (2, ""), 5002, &[("77", 9, "", 10.0, 0)]));
+        // Reprocessing moved the report outside the other report's window.
+        let reprocessed = perspective(processed("a", (1, ""), 7000, &[("99", 9, "", 10.0, 0)]));
+
+        let plan = plan_merge(&reprocessed, [stored(5, 2, &[first, second.clone()])]);
+        assert_eq!(plan.keep, Some((5, 2)));
+        assert!(plan.absorbed.is_empty());
+        assert_eq!(plan.perspectives, [reprocessed, second]);
+    }
+
+    #[test]
+    fn plan_merge_groups_reports_correlated_one_after_another() {
+        let attacker = perspective(processed(
+            "a",
+            (1, "1_100_15"),
+            5000,
+            &[("77", 2, "2_90_3", 10.0, 0)],
+        ));
+        let defender = perspective(processed(
+            "b",
+            (2, "2_90_3"),
+            5004,
+            &[("88", 1, "", 900.0, 0)],
+        ));
+
+        // A batch correlates its reports in turn, so the second report sees
+        // the battle the first one created instead of inserting its own.
+        let first = plan_merge::<u32>(&attacker, Vec::new());
+        assert_eq!(first.keep, None);
+        let second = plan_merge(&defender, [stored(1, 0, &first.perspectives)]);
+        assert_eq!(second.keep, Some((1, 0)));
+        assert_eq!(second.perspectives, [defender, attacker]);
+    }
+
+    #[test]
+    fn battle_document_merges_sides_and_attacks() {
+        let first = perspective(processed(
+            "b",
+            (1, "1_100_15"),
+            5000,
+            &[("77", 9, "9_1_1", 10.0, 0)],
+        ));
+        let second = perspective(processed("a", (3, ""), 5002, &[("77", 9, "", 12.0, 2)]));
+        let document = battle_document(&[first.clone(), second, first]);
+
+        assert_eq!(document["mail_ids"], json!(["a", "b"]));
+        assert_eq!(document["tracking_keys"], json!(["1_100_15", "9_1_1"]));
+        assert_eq!(document["attack_ids"], json!(["77"]));
+        assert_eq!(document["start_time"], json!(5000));
+        assert_eq!(document["end_time"], json!(5032));
+        assert_eq!(document["perspectives"].as_array().map(Vec::len), Some(2));
+
+        let merged = &document["merged"];
+        assert_eq!(merged["reports"], json!(2));
+        let sides = merged["sides"].as_array().expect("sides");
+        assert_eq!(sides.len(), 3);
+        assert_eq!(sides[0]["player_id"], json!(1));
+        assert_eq!(sides[0]["reported_by"], json!(["b"]));
+        assert_eq!(sides[0]["summary"], json!({ "kill_points": 10 }));
+        assert_eq!(sides[2]["player_id"], json!(9));
+        assert_eq!(sides[2]["reported_by"], json!([]));
+        assert_eq!(sides[2]["faced_in"], json!(["b", "a"]));
+
+        let attacks = merged["attacks"].as_array().expect("attacks");
+        assert_eq!(attacks.len(), 1);
+        assert_eq!(attacks[0]["start_time"], json!(5000));
+        assert_eq!(attacks[0]["end_time"], json!(5014));
+        assert_eq!(attacks[0]["tracking_keys"], json!(["9_1_1"]));
+        assert_eq!(attacks[0]["reported_by"], json!(["b", "a"]));
+    }
+}
@@ -25,4 +25,8 @@ pub enum ProcessorError {
     Process(#[from] mail_processor_sdk::ProcessError),
     #[error("bson serialization failed: {0}")]
     BsonEncode(#[from] mongodb::bson::ser::Error),
+    #[error("bson deserialization failed: {0}")]
+    BsonDecode(#[from] mongodb::bson::de::Error),
+    #[error("battle kept changing while correlating mail {0}")]
+    CorrelationConflict(String),
 }
@@ -3,6 +3,7 @@
 //! Background processor for raw mail documents.
 
 mod config;
+mod correlation;
 mod error;
 mod mail;
 mod processing;
@@ -1,18 +1,23 @@
 //! Processing loop and mail handling logic.
 
-use std::sync::Arc;
 use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
 
 use futures::stream::TryStreamExt;
+use mail_processor_sdk::{Parallelism, ProcessStats};
 use mongodb::bson::{Bson, DateTime, Document, oid::ObjectId};
 use serde_json::Value;
 use tracing::{debug, error, info};
 
 use crate::config::Config;
+use crate::correlation::{Perspective, StoredBattle, battle_document, plan_merge};
 use crate::error::ProcessorError;
 use crate::mail::MailType;
 use crate::storage::Storage;
 
+/// Times a report is re-correlated after another writer changed its battles.
+const CORRELATION_ATTEMPTS: usize = 5;
+
 #[derive(Debug)]
 struct RawMail {
     id: ObjectId,
@@ -21,10 +26,36 @@ struct RawMail {
     mail_value: Vec<u8>,
 }
 
+/// A processed Battle report waiting to be correlated.
+#[derive(Debug)]
+struct PendingBattle {
+    id: ObjectId,
+    perspective: Perspective,
+}
+
 /// Run the processor loop forever.
+///
+/// Only the instance holding the processor lease processes batches; other
+/// instances wait until the lease expires, so a second processor started by
+/// mistake stays idle instead of racing on battles.
 pub async fn process_loop(storage: Storage, config: Config) -> Result<(), ProcessorError> {
+    let owner = ObjectId::new();
     loop {
-        match process_batch(&storage, &config).await {
+        match storage.acquire_lease(owner, config.lease_ttl).await {
+            Ok(true) => {}
+            Ok(false) => {
+                debug!("another processor holds the lease");
+                tokio::time::sleep(config.idle_sleep).await;
+                continue;
+            }
+            Err(error) => {
+                error!(error = %error, "acquiring processor lease failed");
+                tokio::time::sleep(config.idle_sleep).await;
+                continue;
+            }
+        }
+
+        match process_batch(&storage, &config, owner).await {
             Ok(0) => tokio::time::sleep(config.idle_sleep).await,
             Ok(_) => {}
             Err(error) => {
@@ -35,30 +66,72 @@ pub async fn process_loop(storage: Storage, config: Config) -> Result<(), Proces
     }
 }
 
-async fn process_batch(storage: &Storage, config: &Config) -> Result<usize, ProcessorError> {
+/// Process a batch of mails concurrently, then correlate Battle reports.
+///
+/// Correlation runs after the concurrent phase on a single task, so reports
+/// of the same fight in one batch see each other's battles instead of racing
+/// to create them. Battle mails are marked processed only once correlated.
+async fn process_batch(
+    storage: &Storage,
+    config: &Config,
+    owner: ObjectId,
+) -> Result<usize, ProcessorError> {
     let cursor = storage.find_pending(config.batch_size).await?;
     let processed = Arc::new(AtomicUsize::new(0));
+    let pending_battles = Arc::new(Mutex::new(Vec::new()));
+    let parallelism = Parallelism::new(config.extractor_workers);
 
     cursor
         .try_for_each_concurrent(config.concurrency, |doc| {
             let storage = storage.clone();
             let processed = Arc::clone(&processed);
+            let pending_battles = Arc::clone(&pending_battles);
             async move {
                 let mail_id = doc.get_str("mail_id").ok().map(str::to_string);
-                if let Err(error) = process_document(&storage, doc).await {
-                    if let Some(mail_id) = mail_id {
-                        error!(error = %error, mail_id = %mail_id, "processing mail failed");
-                    } else {
-                        error!(error = %error, "processing mail failed");
+                match process_document(&storage, doc, parallelism).await {
+                    Ok(Some(pending)) => pending_battles
+                        .lock()
+                        .expect("pending battles lock")
+                        .push(pending),
+                    Ok(None) => {
+                        processed.fetch_add(1, Ordering::Relaxed);
+                    }
+                    Err(error) => {
+                        if let Some(mail_id) = mail_id {
+                            error!(error = %error, mail_id = %mail_id, "processing mail failed");
+                        } else {
+                            error!(error = %error, "processing mail failed");
+                        }
                     }
-                } else {
-                    processed.fetch_add(1, Ordering::Relaxed);
                 }
                 Ok(())
             }
         })
         .await?;
 
+    let pending_battles =
+        std::mem::take(&mut *pending_battles.lock().expect("pending battles lock"));
+    if !pending_battles.is_empty() && !storage.acquire_lease(owner, config.lease_ttl).await? {
+        // The batch outlived the lease; the reports stay pending for the
+        // new holder.
+        error!(
+            skipped = pending_battles.len(),
+            "processor lease lost before correlating battles"
+        );
+        return Ok(processed.load(Ordering::Relaxed));
+    }
+    for pending in pending_battles {
+        let mail_id = pending.perspective.mail_id.clone();
+        match correlate_battle(storage, pending).await {
+            Ok(()) => {
+                processed.fetch_add(1, Ordering::Relaxed);
+            }
+            Err(error) => {
+                error!(error = %error, mail_id = %mail_id, "correlating battle failed");
+            }
+        }
+    }
+
     let processed_count = processed.load(Ordering::Relaxed);
     if processed_count > 0 {
         info!(processed_count, "processed mails");
@@ -69,29 +142,148 @@ async fn process_batch(storage: &Storage, config: &Config) -> Result<usize, Proc
     Ok(processed_count)
 }
 
-async fn process_document(storage: &Storage, doc: Document) -> Result<(), ProcessorError> {
+/// Process and store one mail.
+///
+/// Battle reports with correlation data are returned for correlation instead
+/// of being marked processed here.
+async fn process_document(
+    storage: &Storage,
+    doc: Document,
+    parallelism: Parallelism,
+) -> Result<Option<PendingBattle>, ProcessorError> {
     let raw = parse_raw_mail(doc)?;
     let decoded = decode_mail_value(&raw.mail_value)?;
     let root = normalize_root(&decoded).ok_or_else(|| {
         ProcessorError::InvalidMailPayload("mail payload must be an object".to_string())
     })?;
     let mail_type = extract_mail_type(root)?;
-    let processed = match mail_type {
-        MailType::Battle => mail_processor_battle::process_parallel(root)?,
-        MailType::DuelBattle2 => mail_processor_duelbattle2::process_parallel(root)?,
-        MailType::BarCanyonKillBoss => mail_processor_barcanyonkillboss::process_parallel(root)?,
+    let (processed, mut stats) = match mail_type {
+        MailType::Battle => mail_processor_battle::processor_with_parallelism(parallelism)
+            .process_parallel_with_stats(root),
+        MailType::DuelBattle2 => mail_processor_duelbattle2::process_parallel_with_stats(root),
+        MailType::BarCanyonKillBoss => {
+            mail_processor_barcanyonkillboss::process_parallel_with_stats(root)
+        }
     };
+    stats.set_input_bytes(raw.mail_value.len());
+    log_process_stats(&raw.mail_id, mail_type, &stats);
+    let processed = processed?;
 
     let processed_doc = mongodb::bson::to_document(&processed)?;
     storage
-        .upsert_processed(mail_type, &raw.mail_id, processed_doc)
+        .upsert_processed(mail_type, &raw.mail_id, processed_doc.clone())
         .await?;
+    let pending = if mail_type == MailType::Battle {
+        let processed: Value = mongodb::bson::from_document(processed_doc)?;
+        let perspective = Perspective::from_processed(&processed);
+        if perspective.is_none() {
+            debug!(mail_id = %raw.mail_id, "battle report lacks correlation data");
+        }
+        perspective.map(|perspective| PendingBattle {
+            id: raw.id,
+            perspective,
+        })
+    } else {
+        None
+    };
 
-    let now = DateTime::now();
-    storage.mark_processed(&raw.id, now).await?;
+    if pending.is_none() {
+        let now = DateTime::now();
+        storage.mark_processed(&raw.id, now).await?;
+    }
     debug!(mail_id = %raw.mail_id, status = %raw.status, mail_type = %mail_type, "processed mail");
 
-    Ok(())
+    Ok(pending)
+}
+
+/// Attach a processed Battle report to the battle it belongs to, then mark
+/// the mail processed.
+///
+/// Battles are updated with a version check: when another writer changed a
+/// battle between the read and the write, or an absorbed battle could not be
+/// deleted, the report is correlated again.
+async fn correlate_battle(storage: &Storage, pending: PendingBattle) -> Result<(), ProcessorError> {
+    let perspective = &pending.perspective;
+    for _ in 0..CORRELATION_ATTEMPTS {
+        let candidates = storage
+            .find_battle_candidates(
+                &perspective.mail_id,
+                perspective.start_time,
+                perspective.end_time,
+            )
+            .await?
+            .into_iter()
+            .map(stored_battle)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let plan = plan_merge(perspective, candidates);
+        let battle = mongodb::bson::to_document(&battle_document(&plan.perspectives))?;
+        let saved = match plan.keep {
+            Some((id, version)) => storage.replace_battle(id, version, battle).await?,
+            None => {
+                storage.insert_battle(battle).await?;
+                true
+            }
+        };
+        if !saved {
+            debug!(mail_id = %perspective.mail_id, "battle changed while correlating, retrying");
+            continue;
+        }
+
+        let deleted = storage.delete_battles(&plan.absorbed).await?;
+        if deleted < plan.absorbed.len() as u64 {
+            // An absorbed battle changed and still holds reports now copied
+            // into the kept one; correlate again to fold it in.
+            debug!(mail_id = %perspective.mail_id, "absorbed battle changed, retrying");
+            continue;
+        }
+        storage.mark_processed(&pending.id, DateTime::now()).await?;
+        debug!(
+            mail_id = %perspective.mail_id,
+            reports = plan.perspectives.len(),
+            merged = deleted,
+            "correlated battle report"
+        );
+        return Ok(());
+    }
+
+    Err(ProcessorError::CorrelationConflict(
+        perspective.mail_id.clone(),
+    ))
+}
+
+fn stored_battle(doc: Document) -> Result<StoredBattle<ObjectId>, ProcessorError> {
+    let id = doc
+        .get_object_id("_id")
+        .map_err(|_| ProcessorError::MissingField("_id"))?;
+    let version = doc
+        .get_i64("version")
+        .map_err(|_| ProcessorError::MissingField("version"))?;
+    let battle: Value = mongodb::bson::from_document(doc)?;
+    Ok(StoredBattle::from_json(id, version, &battle))
+}
+
+fn log_process_stats(mail_id: &str, mail_type: MailType, stats: &ProcessStats) {
+    for section in stats.sections() {
+        debug!(
+            mail_id = %mail_id,
+            mail_type = %mail_type,
+            section = section.section(),
+            elapsed_us = section.elapsed().as_micros() as u64,
+            output_len = section.output_len(),
+            outcome = section.outcome().as_str(),
+            "extractor finished"
+        );
+    }
+    debug!(
+        mail_id = %mail_id,
+        mail_type = %mail_type,
+        input_len = stats.input_len(),
+        input_bytes = stats.input_bytes(),
+        elapsed_us = stats.elapsed().as_micros() as u64,
+        slowest = stats.slowest().map(|section| section.section()),
+        "processor finished"
+    );
 }
 
 fn parse_raw_mail(doc: Document) -> Result<RawMail, ProcessorError> {
@@ -223,4 +415,33 @@ mod tests {
         let err = parse_raw_mail(doc).unwrap_err();
         assert!(matches!(err, ProcessorError::MissingField("mail_id")));
     }
+
+    #[test]
+    fn stored_battle_reads_id_version_and_perspectives() {
+        let id = ObjectId::new();
+        let perspective = json!({
+            "mail_id": "mail-1",
+            "mail_time": null,
+            "start_time": 5000_i64,
+            "end_time": 5030_i64,
+            "sender": null,
+            "tracking_key": null,
+            "summary": null,
+            "attacks": [],
+        });
+        let mut doc = mongodb::bson::to_document(&json!({ "perspectives": [perspective] }))
+            .expect("battle doc");
+        doc.insert("_id", id);
+        doc.insert("version", 4_i64);
+
+        let battle = stored_battle(doc.clone()).expect("stored battle");
+        assert_eq!(battle.id, id);
+        assert_eq!(battle.version, 4);
+        assert_eq!(battle.perspectives.len(), 1);
+        assert_eq!(battle.perspectives[0].mail_id, "mail-1");
+
+        doc.remove("version");
+        let err = stored_battle(doc).unwrap_err();
+        assert!(matches!(err, ProcessorError::MissingField("version")));
+    }
 }
@@ -1,24 +1,35 @@
 //! MongoDB access helpers for processor operations.
 
+use std::time::Duration;
+
+use futures::stream::TryStreamExt;
 use mongodb::Collection;
 use mongodb::Cursor;
 use mongodb::IndexModel;
 use mongodb::bson::{DateTime, Document, doc, oid::ObjectId};
+use mongodb::error::{ErrorKind, WriteFailure};
 use mongodb::options::{FindOptions, IndexOptions};
 
+use crate::correlation::TIME_TOLERANCE_SECS;
 use crate::mail::MailType;
 
 pub const STATUS_PENDING: &str = "pending";
 pub const STATUS_REPROCESS: &str = "reprocess";
 pub const STATUS_PROCESSED: &str = "processed";
 
-/// Typed access to raw and processed mail collections.
+/// Id of the lease document that lets a single processor instance run.
+const PROCESSOR_LEASE_ID: &str = "processor";
+const DUPLICATE_KEY_CODE: i32 = 11000;
+
+/// Typed access to raw and processed mail collections and correlated battles.
 #[derive(Debug, Clone)]
 pub struct Storage {
     raw: Collection<Document>,
     battle: Collection<Document>,
     duelbattle2: Collection<Document>,
     barcanyonkillboss: Collection<Document>,
+    battles: Collection<Document>,
+    leases: Collection<Document>,
 }
 
 impl Storage {
@@ -29,6 +40,8 @@ impl Storage {
             battle: db.collection(MailType::Battle.collection_name()),
             duelbattle2: db.collection(MailType::DuelBattle2.collection_name()),
             barcanyonkillboss: db.collection(MailType::BarCanyonKillBoss.collection_name()),
+            battles: db.collection("battles"),
+            leases: db.collection("processor_leases"),
         }
     }
 
@@ -47,6 +60,14 @@ impl Storage {
         self.duelbattle2.create_index(mail_id_index.clone()).await?;
         self.barcanyonkillboss.create_index(mail_id_index).await?;
 
+        let battle_mail_index = IndexModel::builder().keys(doc! { "mail_ids": 1 }).build();
+        self.battles.create_index(battle_mail_index).await?;
+
+        let battle_window_index = IndexModel::builder()
+            .keys(doc! { "start_time": 1, "end_time": 1 })
+            .build();
+        self.battles.create_index(battle_window_index).await?;
+
         Ok(())
     }
 
@@ -89,6 +110,111 @@ impl Storage {
         Ok(())
     }
 
+    /// Fetch battles that contain a mail or whose window is near the given one,
+    /// oldest first.
+    pub async fn find_battle_candidates(
+        &self,
+        mail_id: &str,
+        start_time: u64,
+        end_time: u64,
+    ) -> mongodb::error::Result<Vec<Document>> {
+        let start = start_time.saturating_sub(TIME_TOLERANCE_SECS) as i64;
+        let end = end_time.saturating_add(TIME_TOLERANCE_SECS) as i64;
+        let filter = doc! {
+            "$or": [
+                { "mail_ids": mail_id },
+                { "start_time": { "$lte": end }, "end_time": { "$gte": start } },
+            ]
+        };
+
+        let opts = FindOptions::builder().sort(doc! { "_id": 1 }).build();
+
+        self.battles
+            .find(filter)
+            .with_options(opts)
+            .await?
+            .try_collect()
+            .await
+    }
+
+    /// Insert a new battle at version 0.
+    pub async fn insert_battle(&self, mut doc: Document) -> mongodb::error::Result<()> {
+        doc.insert("version", 0_i64);
+        self.battles.insert_one(doc).await?;
+        Ok(())
+    }
+
+    /// Replace a battle if it is still at `version`, bumping the version.
+    ///
+    /// Returns `false` when another writer changed or deleted the battle since
+    /// it was read.
+    pub async fn replace_battle(
+        &self,
+        id: ObjectId,
+        version: i64,
+        mut doc: Document,
+    ) -> mongodb::error::Result<bool> {
+        doc.insert("version", version + 1);
+        let result = self
+            .battles
+            .replace_one(doc! { "_id": id, "version": version }, doc)
+            .await?;
+        Ok(result.matched_count == 1)
+    }
+
+    /// Delete absorbed battles that are still at the version they were read at.
+    ///
+    /// Battles changed in the meantime are kept so no report is lost, and the
+    /// caller correlates again to fold them in. Returns the number of deleted
+    /// battles.
+    pub async fn delete_battles(&self, battles: &[(ObjectId, i64)]) -> mongodb::error::Result<u64> {
+        if battles.is_empty() {
+            return Ok(0);
+        }
+
+        let filters = battles
+            .iter()
+            .map(|(id, version)| doc! { "_id": id, "version": version })
+            .collect::<Vec<_>>();
+        let result = self.battles.delete_many(doc! { "$or": filters }).await?;
+        Ok(result.deleted_count)
+    }
+
+    /// Take or renew the processor lease for `owner`.
+    ///
+    /// Returns `false` while another instance holds an unexpired lease. Mails
+    /// are not claimed and battles are merged without transactions, so only
+    /// the lease holder may process.
+    pub async fn acquire_lease(
+        &self,
+        owner: ObjectId,
+        ttl: Duration,
+    ) -> mongodb::error::Result<bool> {
+        let now = DateTime::now();
+        let expires_at = DateTime::from_millis(
+            now.timestamp_millis()
+                .saturating_add(i64::try_from(ttl.as_millis()).unwrap_or(i64::MAX)),
+        );
+        let result = self
+            .leases
+            .update_one(
+                doc! {
+                    "_id": PROCESSOR_LEASE_ID,
+                    "$or": [{ "owner": owner }, { "expiresAt": { "$lte": now } }],
+                },
+                doc! { "$set": { "owner": owner, "expiresAt": expires_at } },
+            )
+            .upsert(true)
+            .await;
+
+        match result {
+            Ok(_) => Ok(true),
+            // The upsert collides with the live lease of another instance.
+            Err(error) if is_duplicate_key(&error) => Ok(false),
+            Err(error) => Err(error),
+        }
+    }
+
     /// Mark a raw mail as processed.
     pub async fn mark_processed(&self, id: &ObjectId, now: DateTime) -> mongodb::error::Result<()> {
         self.raw
@@ -106,3 +232,11 @@ impl Storage {
         Ok(())
     }
 }
+
+fn is_duplicate_key(error: &mongodb::error::Error) -> bool {
+    match error.kind.as_ref() {
+        ErrorKind::Write(WriteFailure::WriteError(error)) => error.code == DUPLICATE_KEY_CODE,
+        ErrorKind::Command(error) => error.code == DUPLICATE_KEY_CODE,
+        _ => false,
+    }
+}